@@ -27,16 +27,122 @@ export enum FirefoxStatus {
   Ready = 'READY'
 }
 
+/** How much installing some games would download */
+export type GraphQlDownloadEstimate = {
+  __typename?: 'GraphQLDownloadEstimate';
+  /** The free space where archives are downloaded, in bytes, if it can be told */
+  availableBytes?: Maybe<Scalars['Float']['output']>;
+  /** The total size of the games whose size was found, in bytes */
+  totalBytes: Scalars['Float']['output'];
+  /** The games whose size couldn't be found */
+  unknown: Array<Scalars['GameId']['output']>;
+};
+
+/** Where one of a game's sources really downloads it from, or why that couldn't be found */
+export type GraphQlDownloadLink = {
+  __typename?: 'GraphQLDownloadLink';
+  error?: Maybe<Scalars['String']['output']>;
+  source: Scalars['String']['output'];
+  /** How the URL was found */
+  strategy?: Maybe<GraphQlLinkStrategy>;
+  url?: Maybe<Scalars['String']['output']>;
+};
+
+/** What was being done with a game when it failed */
+export enum GraphQlErrorStage {
+  Download = 'DOWNLOAD',
+  Install = 'INSTALL',
+  Run = 'RUN'
+}
+
 export type GraphQlGame = {
   __typename?: 'GraphQLGame';
+  /**
+   * Whether the game's archive is already downloaded or cached, so installing it only has to
+   * extract it
+   */
+  archiveDownloaded: Scalars['Boolean']['output'];
+  changelog?: Maybe<Scalars['String']['output']>;
+  /** Percent of the CPU the game and the processes it started are using, while it's running */
+  cpuUsage?: Maybe<Scalars['Float']['output']>;
+  description?: Maybe<Scalars['String']['output']>;
+  /** The last errors the game had, newest first */
+  errors: Array<GraphQlGameError>;
+  /** The full path of the executable running the game starts */
+  exePath: Scalars['String']['output'];
+  /** The exit code the game had the last time it was run, if it's exited with one */
+  exitCode?: Maybe<Scalars['Int']['output']>;
+  /** Whether the game has exited since it was last run */
+  exited: Scalars['Boolean']['output'];
+  /**
+   * A web page about the game, like one explaining its controls, if it has one that can be
+   * opened
+   */
+  homepage?: Maybe<Scalars['String']['output']>;
+  /** Why the last hook the game ran failed, if it did */
+  hookFailure?: Maybe<Scalars['String']['output']>;
+  /** Why the game's hooks don't compile, if they don't */
+  hooksError?: Maybe<Scalars['String']['output']>;
   icon: Scalars['String']['output'];
   id: Scalars['Int']['output'];
+  /** The folder the game is installed in instead of the games folder, if it has one */
+  installDir?: Maybe<Scalars['String']['output']>;
+  /** Bytes of memory the game and the processes it started are using, while it's running */
+  memoryUsage?: Maybe<Scalars['Float']['output']>;
   name: Scalars['String']['output'];
+  /** The last lines the game printed the last time it was run */
+  output: Array<Scalars['String']['output']>;
+  /** Whether the game is kept on the version it's on when the game list changes */
+  pinned: Scalars['Boolean']['output'];
+  /**
+   * Whether the game's executable was missing or blocked the last time it was run, which is
+   * usually an antivirus quarantining it
+   */
+  quarantined: Scalars['Boolean']['output'];
+  /** Where the game's download resumed from in bytes, if it picked up an interrupted one */
+  resumedAt?: Maybe<Scalars['Float']['output']>;
+  /** The name of the hook the game is running, like `post_install`, if it's running one */
+  runningHook?: Maybe<Scalars['String']['output']>;
+  /** Whether the game has made a save folder yet */
+  saveDirExists: Scalars['Boolean']['output'];
+  /** The size of the game's save folder in bytes, or `null` while it's being measured */
+  saveSize?: Maybe<Scalars['Float']['output']>;
   status: GraphQlGameStatus;
+  /** Lowercase categories, like "puzzle" */
+  tags: Array<Scalars['String']['output']>;
+  /** Whether the server has a different version of the game than the pinned one */
+  updateHeld: Scalars['Boolean']['output'];
+  /** The folder the game is run in */
+  workingDir: Scalars['String']['output'];
+};
+
+/** A game an audit found missing or damaged files in */
+export type GraphQlGameAudit = {
+  __typename?: 'GraphQLGameAudit';
+  /** The missing or damaged files, relative to the game's folder */
+  damagedFiles: Array<Scalars['String']['output']>;
+  game: Scalars['GameId']['output'];
+  name?: Maybe<Scalars['String']['output']>;
+  /** About how many bytes repairing the game downloads (before compression) */
+  repairSize: Scalars['Float']['output'];
+};
+
+export type GraphQlGameError = {
+  __typename?: 'GraphQLGameError';
+  message: Scalars['String']['output'];
+  stage: GraphQlErrorStage;
+  /** When the error happened, in milliseconds since the Unix epoch */
+  time: Scalars['Float']['output'];
 };
 
 export type GraphQlGameStatus = {
   __typename?: 'GraphQLGameStatus';
+  /** Why the last install failed, if it did */
+  error?: Maybe<Scalars['String']['output']>;
+  /** Exact progress: bytes while downloading, files while installing */
+  exactProgress?: Maybe<Array<Scalars['Float']['output']>>;
+  /** Whether the game is downloading, but downloads are paused */
+  paused: Scalars['Boolean']['output'];
   /** Progress in megabytes */
   progress?: Maybe<Array<Scalars['Int']['output']>>;
   status: GraphQlGameStatusInner;
@@ -44,19 +150,196 @@ export type GraphQlGameStatus = {
 
 export enum GraphQlGameStatusInner {
   Downloading = 'DOWNLOADING',
+  Failed = 'FAILED',
   Installing = 'INSTALLING',
   NotDownloaded = 'NOT_DOWNLOADED',
   Ready = 'READY',
   Running = 'RUNNING'
 }
 
+/** The last check of every installed game's files */
+export type GraphQlLibraryAudit = {
+  __typename?: 'GraphQLLibraryAudit';
+  /** How many games have been checked */
+  checked: Scalars['Int']['output'];
+  /** How many files are missing or damaged, in every game */
+  damagedFiles: Scalars['Int']['output'];
+  /** The games with missing or damaged files, which need to be repaired */
+  games: Array<GraphQlGameAudit>;
+  /** About how many bytes repairing every game that needs it downloads (before compression) */
+  repairSize: Scalars['Float']['output'];
+  /** Whether the audit is still checking games */
+  running: Scalars['Boolean']['output'];
+  /** How many games are being checked */
+  total: Scalars['Int']['output'];
+};
+
+/** How a download URL was found */
+export enum GraphQlLinkStrategy {
+  Direct = 'DIRECT',
+  DriveFormAction = 'DRIVE_FORM_ACTION',
+  DriveHiddenInputs = 'DRIVE_HIDDEN_INPUTS',
+  DriveUnconfirmed = 'DRIVE_UNCONFIRMED'
+}
+
+/** What the app has downloaded and installed since it started, for monitoring */
+export type GraphQlStats = {
+  __typename?: 'GraphQLStats';
+  activeDownloads: Scalars['Int']['output'];
+  activeInstalls: Scalars['Int']['output'];
+  /** Bytes downloaded, including downloads that failed partway */
+  bytesDownloaded: Scalars['Float']['output'];
+  installsCompleted: Scalars['Int']['output'];
+  /** Installs that failed, not counting canceled ones */
+  installsFailed: Scalars['Int']['output'];
+};
+
 export type Mutation = {
   __typename?: 'Mutation';
+  /**
+   * Updates a pinned game to the server's version, keeping it pinned. Returns whether there
+   * was an update.
+   */
+  applyUpdate: Scalars['Boolean']['output'];
+  /** Checks every installed game's files in the background, see `libraryAudit`. */
+  auditLibrary: VoidEnum;
+  /**
+   * Stops installing a game. The downloaded archive is kept, so installing it again doesn't
+   * download it again.
+   */
+  cancelInstall: VoidEnum;
+  /**
+   * Removes leftover archives, partial downloads and failed installs from the games directory,
+   * returning how many bytes were freed.
+   */
+  cleanUp: Scalars['Float']['output'];
+  /** Deletes the kept archives, returning how many bytes were freed. */
+  clearArchiveCache: Scalars['Float']['output'];
+  /** Forgets the errors a game has had. */
+  clearErrors: VoidEnum;
+  completeSetup: VoidEnum;
   delete: VoidEnum;
   download: VoidEnum;
+  /**
+   * Installs the games, downloading them in the given order. Games that can't be installed
+   * right now are skipped. Returns how many are being installed.
+   */
+  downloadAll: Scalars['Int']['output'];
+  /**
+   * Installs a game from an archive that's already on this computer (like on a USB drive)
+   * instead of downloading it. The archive itself is left alone.
+   */
+  importArchive: VoidEnum;
   launchFirefox: FirefoxStatus;
+  /**
+   * Moves a game to `position` in the install queue, counting from 0, so its download starts
+   * before or after the others. Returns whether it was in the queue.
+   */
+  moveInInstallQueue: Scalars['Boolean']['output'];
+  /**
+   * Opens Windows Security's virus & threat protection settings, where the games folder can be
+   * excluded so games aren't quarantined.
+   */
+  openAntivirusExclusions: VoidEnum;
+  /** Opens a game's homepage in the browser. */
+  openHomepage: VoidEnum;
+  /** Opens a game's save folder in the file manager, creating it if the game hasn't yet. */
+  openSavesFolder: VoidEnum;
+  pauseDownloads: VoidEnum;
+  /**
+   * Deletes an installed game's files and installs it again from a fresh download, running its
+   * `post_install` hook again. Its saves are kept.
+   */
+  reinstall: VoidEnum;
+  /**
+   * Takes a game out of the install queue, canceling its install. Returns whether it was in
+   * the queue.
+   */
+  removeFromInstallQueue: Scalars['Boolean']['output'];
+  /**
+   * Reinstalls each of `games`, like after an audit found them damaged. Returns how many are
+   * being reinstalled; the ones that can't be are skipped.
+   */
+  repair: Scalars['Int']['output'];
+  /**
+   * Runs the latest version of an installed game's `post_install` hook again, without
+   * reinstalling it. Unless `force` is set, nothing is done if the hook has already run from
+   * the same hooks. Returns whether it's being run.
+   */
+  rerunSetup: Scalars['Boolean']['output'];
+  resumeDownloads: VoidEnum;
   run: VoidEnum;
-  updateGameList: VoidEnum;
+  /** Shows or hides details most people don't need, like how games are launched. */
+  setAdvanced: VoidEnum;
+  /**
+   * Limits how many bytes of archives are kept, or removes the limit if it's `null`. The least
+   * recently used archives are deleted to get under it.
+   */
+  setArchiveCacheLimit: VoidEnum;
+  /** Sets how many times a download that fails partway is retried, from 0 to 10. */
+  setDownloadRetries: VoidEnum;
+  setGamesDir: VoidEnum;
+  /**
+   * Installs a game in `path` instead of the games folder, or back in the games folder if
+   * it's `null`. An installed game is moved there.
+   */
+  setInstallDir: VoidEnum;
+  /**
+   * Keeps downloaded archives after installing them, so reinstalling a game doesn't download
+   * it again.
+   */
+  setKeepArchives: VoidEnum;
+  /** Switches between showing the games as a list or a grid. */
+  setListView: VoidEnum;
+  setLocale: VoidEnum;
+  /**
+   * Sets how many downloads and extractions can run at once, from 1 to 16. Waiting ones start
+   * right away if there's now room for them.
+   */
+  setMaxConcurrentWork: VoidEnum;
+  /** Keeps a game on the version it's on, or lets it be updated with the game list again. */
+  setPinned: VoidEnum;
+  /**
+   * Sets how many hosts are asked for a game's size at once before installing several games,
+   * from 1 to 16.
+   */
+  setPreflightConcurrency: VoidEnum;
+  /**
+   * Sets the HTTP or SOCKS5 proxy to download through. `null` goes back to the `HTTPS_PROXY`
+   * environment variable, if there is one.
+   */
+  setProxy: VoidEnum;
+  setSavesDir: VoidEnum;
+  /**
+   * Downloads archives to `path` instead of the games folder, or to the games folder again if
+   * it's `null`. Archives that were already downloaded aren't moved.
+   */
+  setStagingDir: VoidEnum;
+  /**
+   * Checks that a game's files are there before running it, marking it as failed so it can be
+   * reinstalled if they aren't.
+   */
+  setVerifyBeforeRun: VoidEnum;
+  /**
+   * Fetches the game list now instead of waiting for the next refresh, returning how many
+   * games were added.
+   */
+  updateGameList: Scalars['Int']['output'];
+};
+
+
+export type MutationApplyUpdateArgs = {
+  game: Scalars['GameId']['input'];
+};
+
+
+export type MutationCancelInstallArgs = {
+  game: Scalars['GameId']['input'];
+};
+
+
+export type MutationClearErrorsArgs = {
+  game: Scalars['GameId']['input'];
 };
 
 
@@ -70,15 +353,221 @@ export type MutationDownloadArgs = {
 };
 
 
+export type MutationDownloadAllArgs = {
+  games: Array<Scalars['GameId']['input']>;
+};
+
+
+export type MutationImportArchiveArgs = {
+  game: Scalars['GameId']['input'];
+  path: Scalars['String']['input'];
+};
+
+
+export type MutationMoveInInstallQueueArgs = {
+  game: Scalars['GameId']['input'];
+  position: Scalars['Int']['input'];
+};
+
+
+export type MutationOpenHomepageArgs = {
+  game: Scalars['GameId']['input'];
+};
+
+
+export type MutationOpenSavesFolderArgs = {
+  game: Scalars['GameId']['input'];
+};
+
+
+export type MutationReinstallArgs = {
+  game: Scalars['GameId']['input'];
+};
+
+
+export type MutationRemoveFromInstallQueueArgs = {
+  game: Scalars['GameId']['input'];
+};
+
+
+export type MutationRepairArgs = {
+  games: Array<Scalars['GameId']['input']>;
+};
+
+
+export type MutationRerunSetupArgs = {
+  force?: InputMaybe<Scalars['Boolean']['input']>;
+  game: Scalars['GameId']['input'];
+};
+
+
 export type MutationRunArgs = {
   game: Scalars['GameId']['input'];
 };
 
+
+export type MutationSetAdvancedArgs = {
+  advanced: Scalars['Boolean']['input'];
+};
+
+
+export type MutationSetArchiveCacheLimitArgs = {
+  bytes?: InputMaybe<Scalars['Float']['input']>;
+};
+
+
+export type MutationSetDownloadRetriesArgs = {
+  retries: Scalars['Int']['input'];
+};
+
+
+export type MutationSetGamesDirArgs = {
+  path: Scalars['String']['input'];
+};
+
+
+export type MutationSetInstallDirArgs = {
+  game: Scalars['GameId']['input'];
+  path?: InputMaybe<Scalars['String']['input']>;
+};
+
+
+export type MutationSetKeepArchivesArgs = {
+  keepArchives: Scalars['Boolean']['input'];
+};
+
+
+export type MutationSetListViewArgs = {
+  listView: Scalars['Boolean']['input'];
+};
+
+
+export type MutationSetLocaleArgs = {
+  locale?: InputMaybe<Scalars['String']['input']>;
+};
+
+
+export type MutationSetMaxConcurrentWorkArgs = {
+  limit: Scalars['Int']['input'];
+};
+
+
+export type MutationSetPreflightConcurrencyArgs = {
+  limit: Scalars['Int']['input'];
+};
+
+
+export type MutationSetPinnedArgs = {
+  game: Scalars['GameId']['input'];
+  pinned: Scalars['Boolean']['input'];
+};
+
+
+export type MutationSetProxyArgs = {
+  proxy?: InputMaybe<Scalars['String']['input']>;
+};
+
+
+export type MutationSetSavesDirArgs = {
+  path: Scalars['String']['input'];
+};
+
+
+export type MutationSetStagingDirArgs = {
+  path?: InputMaybe<Scalars['String']['input']>;
+};
+
+
+export type MutationSetVerifyBeforeRunArgs = {
+  verifyBeforeRun: Scalars['Boolean']['input'];
+};
+
 export type Query = {
   __typename?: 'Query';
+  /** Whether details most people don't need, like how games are launched, are shown */
+  advanced: Scalars['Boolean']['output'];
+  /** The size of the kept archives, in bytes */
+  archiveCacheBytes: Scalars['Float']['output'];
+  /** The most bytes of archives that are kept, or `null` for no limit */
+  archiveCacheLimit?: Maybe<Scalars['Float']['output']>;
+  /** A bug report with the recent logs and the config, for the user to send */
+  bugReport: Scalars['String']['output'];
+  /** The folder archives are downloaded to instead of the games folder, if there is one */
+  customStagingDir?: Maybe<Scalars['String']['output']>;
+  /**
+   * How much installing the games would download, asking their hosts a few at a time so it's
+   * known before installing them all
+   */
+  downloadEstimate: GraphQlDownloadEstimate;
+  /**
+   * Where each of a game's sources really downloads it from, without downloading it, to tell
+   * whether a failing download is the host's fault
+   */
+  downloadLinks: Array<GraphQlDownloadLink>;
+  /** How many times a download that fails partway is retried */
+  downloadRetries: Scalars['Int']['output'];
+  /**
+   * When downloads can start again, in milliseconds since the Unix epoch, if it's outside the
+   * download window. Until then, queued downloads wait.
+   */
+  downloadWindowOpens?: Maybe<Scalars['Float']['output']>;
+  downloadsPaused: Scalars['Boolean']['output'];
   firefox: FirefoxStatus;
   game?: Maybe<GraphQlGame>;
+  /** When the game list was last updated, in milliseconds since the Unix epoch */
+  gameListUpdated?: Maybe<Scalars['Float']['output']>;
   games: Array<GraphQlGame>;
+  gamesDir: Scalars['String']['output'];
+  /** Whether the games directory exists and can be written to */
+  gamesDirAvailable: Scalars['Boolean']['output'];
+  /** The games waiting for their download to start, first to start first */
+  installQueue: Array<Scalars['GameId']['output']>;
+  /**
+   * Whether downloaded archives are kept after installing, so reinstalling doesn't download
+   * them again
+   */
+  keepArchives: Scalars['Boolean']['output'];
+  /** The last check of every installed game's files */
+  libraryAudit: GraphQlLibraryAudit;
+  /** Whether the games are shown as a list instead of a grid */
+  listView: Scalars['Boolean']['output'];
+  /** The UI language, e.g. `en-US` */
+  locale: Scalars['String']['output'];
+  /** How many downloads and extractions can run at once */
+  maxConcurrentWork: Scalars['Int']['output'];
+  /** A saves folder in the user's OneDrive, if it's set up, to offer in the wizard */
+  onedriveSavesDir?: Maybe<Scalars['String']['output']>;
+  /** How many bytes of leftover files the clean up would remove */
+  orphanedBytes: Scalars['Float']['output'];
+  /** How many hosts are asked for a game's size at once before installing several games */
+  preflightConcurrency: Scalars['Int']['output'];
+  /** The proxy downloads go through, if one is set */
+  proxy?: Maybe<Scalars['String']['output']>;
+  savesDir: Scalars['String']['output'];
+  /** Whether the first run wizard has been finished */
+  setupComplete: Scalars['Boolean']['output'];
+  /** The games in the last game list that couldn't be read, and why */
+  skippedGames: Array<Scalars['String']['output']>;
+  /** The folder archives are downloaded to before they're installed */
+  stagingDir: Scalars['String']['output'];
+  /** What the app has downloaded and installed since it started */
+  stats: GraphQlStats;
+  /** Whether a game's files are checked before it's run */
+  verifyBeforeRun: Scalars['Boolean']['output'];
+};
+
+
+export type QueryBugReportArgs = {
+  error?: InputMaybe<Scalars['String']['input']>;
+};
+
+
+export type QueryDownloadEstimateArgs = {
+  games: Array<Scalars['GameId']['input']>;
+};
+
+export type QueryDownloadLinksArgs = {
+  game: Scalars['GameId']['input'];
 };
 
 
@@ -114,20 +603,302 @@ export type RunGameMutation = { __typename?: 'Mutation', run: VoidEnum };
 export type UpdateGamesMutationVariables = Exact<{ [key: string]: never; }>;
 
 
-export type UpdateGamesMutation = { __typename?: 'Mutation', updateGameList: VoidEnum };
+export type UpdateGamesMutation = { __typename?: 'Mutation', updateGameList: number };
 
 export type LaunchFirefoxMutationVariables = Exact<{ [key: string]: never; }>;
 
 
 export type LaunchFirefoxMutation = { __typename?: 'Mutation', launchFirefox: FirefoxStatus };
 
+export type PauseDownloadsMutationVariables = Exact<{ [key: string]: never; }>;
+
+
+export type PauseDownloadsMutation = { __typename?: 'Mutation', pauseDownloads: VoidEnum };
+
+export type ResumeDownloadsMutationVariables = Exact<{ [key: string]: never; }>;
+
+
+export type ResumeDownloadsMutation = { __typename?: 'Mutation', resumeDownloads: VoidEnum };
+
+export type SetLocaleMutationVariables = Exact<{
+  locale?: InputMaybe<Scalars['String']['input']>;
+}>;
+
+
+export type SetLocaleMutation = { __typename?: 'Mutation', setLocale: VoidEnum };
+
+export type SetGamesDirMutationVariables = Exact<{
+  path: Scalars['String']['input'];
+}>;
+
+
+export type SetGamesDirMutation = { __typename?: 'Mutation', setGamesDir: VoidEnum };
+
+export type SetSavesDirMutationVariables = Exact<{
+  path: Scalars['String']['input'];
+}>;
+
+
+export type SetSavesDirMutation = { __typename?: 'Mutation', setSavesDir: VoidEnum };
+
+export type CompleteSetupMutationVariables = Exact<{ [key: string]: never; }>;
+
+
+export type CompleteSetupMutation = { __typename?: 'Mutation', completeSetup: VoidEnum };
+
+export type CleanUpMutationVariables = Exact<{ [key: string]: never; }>;
+
+
+export type CleanUpMutation = { __typename?: 'Mutation', cleanUp: number };
+
+export type RerunSetupMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+  force?: InputMaybe<Scalars['Boolean']['input']>;
+}>;
+
+
+export type RerunSetupMutation = { __typename?: 'Mutation', rerunSetup: boolean };
+
+export type SetListViewMutationVariables = Exact<{
+  listView: Scalars['Boolean']['input'];
+}>;
+
+
+export type SetListViewMutation = { __typename?: 'Mutation', setListView: VoidEnum };
+
+export type CancelInstallMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type CancelInstallMutation = { __typename?: 'Mutation', cancelInstall: VoidEnum };
+
+export type OpenSavesFolderMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type OpenSavesFolderMutation = { __typename?: 'Mutation', openSavesFolder: VoidEnum };
+
+export type OpenHomepageMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type OpenHomepageMutation = { __typename?: 'Mutation', openHomepage: VoidEnum };
+
+export type OpenAntivirusExclusionsMutationVariables = Exact<{ [key: string]: never; }>;
+
+
+export type OpenAntivirusExclusionsMutation = { __typename?: 'Mutation', openAntivirusExclusions: VoidEnum };
+
+export type ImportArchiveMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+  path: Scalars['String']['input'];
+}>;
+
+
+export type ImportArchiveMutation = { __typename?: 'Mutation', importArchive: VoidEnum };
+
+export type SetInstallDirMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+  path?: InputMaybe<Scalars['String']['input']>;
+}>;
+
+
+export type SetInstallDirMutation = { __typename?: 'Mutation', setInstallDir: VoidEnum };
+
+export type SetKeepArchivesMutationVariables = Exact<{
+  keepArchives: Scalars['Boolean']['input'];
+}>;
+
+
+export type SetKeepArchivesMutation = { __typename?: 'Mutation', setKeepArchives: VoidEnum };
+
+export type ClearArchiveCacheMutationVariables = Exact<{ [key: string]: never; }>;
+
+
+export type ClearArchiveCacheMutation = { __typename?: 'Mutation', clearArchiveCache: number };
+
+export type SetVerifyBeforeRunMutationVariables = Exact<{
+  verifyBeforeRun: Scalars['Boolean']['input'];
+}>;
+
+
+export type SetVerifyBeforeRunMutation = { __typename?: 'Mutation', setVerifyBeforeRun: VoidEnum };
+
+export type SetArchiveCacheLimitMutationVariables = Exact<{
+  bytes?: InputMaybe<Scalars['Float']['input']>;
+}>;
+
+
+export type SetArchiveCacheLimitMutation = { __typename?: 'Mutation', setArchiveCacheLimit: VoidEnum };
+
+export type SetAdvancedMutationVariables = Exact<{
+  advanced: Scalars['Boolean']['input'];
+}>;
+
+
+export type SetAdvancedMutation = { __typename?: 'Mutation', setAdvanced: VoidEnum };
+
+export type SetDownloadRetriesMutationVariables = Exact<{
+  retries: Scalars['Int']['input'];
+}>;
+
+
+export type SetDownloadRetriesMutation = { __typename?: 'Mutation', setDownloadRetries: VoidEnum };
+
+export type SetMaxConcurrentWorkMutationVariables = Exact<{
+  limit: Scalars['Int']['input'];
+}>;
+
+
+export type SetMaxConcurrentWorkMutation = { __typename?: 'Mutation', setMaxConcurrentWork: VoidEnum };
+
+export type SetStagingDirMutationVariables = Exact<{
+  path?: InputMaybe<Scalars['String']['input']>;
+}>;
+
+
+export type SetStagingDirMutation = { __typename?: 'Mutation', setStagingDir: VoidEnum };
+
+export type ReinstallGameMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type ReinstallGameMutation = { __typename?: 'Mutation', reinstall: VoidEnum };
+
+export type ClearErrorsMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type ClearErrorsMutation = { __typename?: 'Mutation', clearErrors: VoidEnum };
+
+export type SetPinnedMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+  pinned: Scalars['Boolean']['input'];
+}>;
+
+
+export type SetPinnedMutation = { __typename?: 'Mutation', setPinned: VoidEnum };
+
+export type ApplyUpdateMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type ApplyUpdateMutation = { __typename?: 'Mutation', applyUpdate: boolean };
+
+export type AuditLibraryMutationVariables = Exact<{ [key: string]: never; }>;
+
+
+export type AuditLibraryMutation = { __typename?: 'Mutation', auditLibrary: VoidEnum };
+
+export type RepairMutationVariables = Exact<{
+  games: Array<Scalars['GameId']['input']> | Scalars['GameId']['input'];
+}>;
+
+
+export type RepairMutation = { __typename?: 'Mutation', repair: number };
+
+export type MoveInInstallQueueMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+  position: Scalars['Int']['input'];
+}>;
+
+
+export type MoveInInstallQueueMutation = { __typename?: 'Mutation', moveInInstallQueue: boolean };
+
+export type RemoveFromInstallQueueMutationVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type RemoveFromInstallQueueMutation = { __typename?: 'Mutation', removeFromInstallQueue: boolean };
+
+export type DownloadAllMutationVariables = Exact<{
+  games: Array<Scalars['GameId']['input']> | Scalars['GameId']['input'];
+}>;
+
+
+export type DownloadAllMutation = { __typename?: 'Mutation', downloadAll: number };
+
+export type SetPreflightConcurrencyMutationVariables = Exact<{
+  limit: Scalars['Int']['input'];
+}>;
+
+
+export type SetPreflightConcurrencyMutation = { __typename?: 'Mutation', setPreflightConcurrency: VoidEnum };
+
 export type GamesQueryVariables = Exact<{ [key: string]: never; }>;
 
 
-export type GamesQuery = { __typename?: 'Query', games: Array<{ __typename?: 'GraphQLGame', id: number, name: string, icon: string, status: { __typename?: 'GraphQLGameStatus', status: GraphQlGameStatusInner, progress?: Array<number> | null } }> };
+export type GamesQuery = { __typename?: 'Query', downloadsPaused: boolean, downloadWindowOpens?: number | null, setupComplete: boolean, listView: boolean, verifyBeforeRun: boolean, advanced: boolean, gamesDir: string, gamesDirAvailable: boolean, gameListUpdated?: number | null, skippedGames: Array<string>, installQueue: Array<any>, games: Array<{ __typename?: 'GraphQLGame', id: number, name: string, icon: string, description?: string | null, changelog?: string | null, homepage?: string | null, hooksError?: string | null, runningHook?: string | null, hookFailure?: string | null, archiveDownloaded: boolean, tags: Array<string>, saveDirExists: boolean, saveSize?: number | null, installDir?: string | null, exePath: string, workingDir: string, cpuUsage?: number | null, memoryUsage?: number | null, pinned: boolean, updateHeld: boolean, resumedAt?: number | null, quarantined: boolean, status: { __typename?: 'GraphQLGameStatus', status: GraphQlGameStatusInner, exactProgress?: Array<number> | null, paused: boolean, error?: string | null }, errors: Array<{ __typename?: 'GraphQLGameError', time: number, stage: GraphQlErrorStage, message: string }> }> };
 
 
-export const DeleteGameDoc = gql`
+export type LocaleQueryVariables = Exact<{ [key: string]: never; }>;
+
+
+export type LocaleQuery = { __typename?: 'Query', locale: string };
+
+export type SetupQueryVariables = Exact<{ [key: string]: never; }>;
+
+
+export type SetupQuery = { __typename?: 'Query', setupComplete: boolean, gamesDir: string, savesDir: string, onedriveSavesDir?: string | null };
+
+export type OrphanedBytesQueryVariables = Exact<{ [key: string]: never; }>;
+
+
+export type OrphanedBytesQuery = { __typename?: 'Query', orphanedBytes: number };
+
+export type BugReportQueryVariables = Exact<{
+  error?: InputMaybe<Scalars['String']['input']>;
+}>;
+
+
+export type BugReportQuery = { __typename?: 'Query', bugReport: string };
+
+export type GameOutputQueryVariables = Exact<{
+  id: Scalars['Int']['input'];
+}>;
+
+
+export type GameOutputQuery = { __typename?: 'Query', game?: { __typename?: 'GraphQLGame', output: Array<string>, exited: boolean, exitCode?: number | null } | null };
+
+export type ArchiveCacheQueryVariables = Exact<{ [key: string]: never; }>;
+
+
+export type ArchiveCacheQuery = { __typename?: 'Query', keepArchives: boolean, archiveCacheBytes: number, archiveCacheLimit?: number | null };
+
+
+export type SettingsQueryVariables = Exact<{ [key: string]: never; }>;
+
+
+export type SettingsQuery = { __typename?: 'Query', downloadRetries: number, maxConcurrentWork: number, preflightConcurrency: number, stagingDir: string, customStagingDir?: string | null };
+
+
+export type LibraryAuditQueryVariables = Exact<{ [key: string]: never; }>;
+
+
+export type LibraryAuditQuery = { __typename?: 'Query', libraryAudit: { __typename?: 'GraphQLLibraryAudit', running: boolean, checked: number, total: number, damagedFiles: number, repairSize: number, games: Array<{ __typename?: 'GraphQLGameAudit', game: any, name?: string | null, damagedFiles: Array<string>, repairSize: number }> } };
+
+export type DownloadLinksQueryVariables = Exact<{
+  game: Scalars['GameId']['input'];
+}>;
+
+
+export type DownloadLinksQuery = { __typename?: 'Query', downloadLinks: Array<{ __typename?: 'GraphQLDownloadLink', source: string, url?: string | null, strategy?: GraphQlLinkStrategy | null, error?: string | null }> };
+
+export type DownloadEstimateQueryVariables = Exact<{
+  games: Array<Scalars['GameId']['input']> | Scalars['GameId']['input'];
+}>;
+
+
+export type DownloadEstimateQuery = { __typename?: 'Query', downloadEstimate: { __typename?: 'GraphQLDownloadEstimate', totalBytes: number, unknown: Array<any>, availableBytes?: number | null } };
+
+export const DeleteGameDoc = gql`
     mutation DeleteGame($game: GameId!) {
   delete(game: $game)
 }
@@ -152,19 +923,305 @@ export const LaunchFirefoxDoc = gql`
   launchFirefox
 }
     `;
+export const PauseDownloadsDoc = gql`
+    mutation PauseDownloads {
+  pauseDownloads
+}
+    `;
+export const ResumeDownloadsDoc = gql`
+    mutation ResumeDownloads {
+  resumeDownloads
+}
+    `;
+export const SetLocaleDoc = gql`
+    mutation SetLocale($locale: String) {
+  setLocale(locale: $locale)
+}
+    `;
+export const SetGamesDirDoc = gql`
+    mutation SetGamesDir($path: String!) {
+  setGamesDir(path: $path)
+}
+    `;
+export const SetSavesDirDoc = gql`
+    mutation SetSavesDir($path: String!) {
+  setSavesDir(path: $path)
+}
+    `;
+export const CompleteSetupDoc = gql`
+    mutation CompleteSetup {
+  completeSetup
+}
+    `;
+export const CleanUpDoc = gql`
+    mutation CleanUp {
+  cleanUp
+}
+    `;
+export const RerunSetupDoc = gql`
+    mutation RerunSetup($game: GameId!, $force: Boolean) {
+  rerunSetup(game: $game, force: $force)
+}
+    `;
+export const SetListViewDoc = gql`
+    mutation SetListView($listView: Boolean!) {
+  setListView(listView: $listView)
+}
+    `;
+export const CancelInstallDoc = gql`
+    mutation CancelInstall($game: GameId!) {
+  cancelInstall(game: $game)
+}
+    `;
+export const OpenSavesFolderDoc = gql`
+    mutation OpenSavesFolder($game: GameId!) {
+  openSavesFolder(game: $game)
+}
+    `;
+export const OpenHomepageDoc = gql`
+    mutation OpenHomepage($game: GameId!) {
+  openHomepage(game: $game)
+}
+    `;
+export const OpenAntivirusExclusionsDoc = gql`
+    mutation OpenAntivirusExclusions {
+  openAntivirusExclusions
+}
+    `;
+export const ImportArchiveDoc = gql`
+    mutation ImportArchive($game: GameId!, $path: String!) {
+  importArchive(game: $game, path: $path)
+}
+    `;
+export const SetInstallDirDoc = gql`
+    mutation SetInstallDir($game: GameId!, $path: String) {
+  setInstallDir(game: $game, path: $path)
+}
+    `;
+export const SetKeepArchivesDoc = gql`
+    mutation SetKeepArchives($keepArchives: Boolean!) {
+  setKeepArchives(keepArchives: $keepArchives)
+}
+    `;
+export const ClearArchiveCacheDoc = gql`
+    mutation ClearArchiveCache {
+  clearArchiveCache
+}
+    `;
+export const SetVerifyBeforeRunDoc = gql`
+    mutation SetVerifyBeforeRun($verifyBeforeRun: Boolean!) {
+  setVerifyBeforeRun(verifyBeforeRun: $verifyBeforeRun)
+}
+    `;
+export const SetArchiveCacheLimitDoc = gql`
+    mutation SetArchiveCacheLimit($bytes: Float) {
+  setArchiveCacheLimit(bytes: $bytes)
+}
+    `;
+export const SetAdvancedDoc = gql`
+    mutation SetAdvanced($advanced: Boolean!) {
+  setAdvanced(advanced: $advanced)
+}
+    `;
+export const SetDownloadRetriesDoc = gql`
+    mutation SetDownloadRetries($retries: Int!) {
+  setDownloadRetries(retries: $retries)
+}
+    `;
+export const SetMaxConcurrentWorkDoc = gql`
+    mutation SetMaxConcurrentWork($limit: Int!) {
+  setMaxConcurrentWork(limit: $limit)
+}
+    `;
+export const SetStagingDirDoc = gql`
+    mutation SetStagingDir($path: String) {
+  setStagingDir(path: $path)
+}
+    `;
+export const ReinstallGameDoc = gql`
+    mutation ReinstallGame($game: GameId!) {
+  reinstall(game: $game)
+}
+    `;
+export const ClearErrorsDoc = gql`
+    mutation ClearErrors($game: GameId!) {
+  clearErrors(game: $game)
+}
+    `;
+export const SetPinnedDoc = gql`
+    mutation SetPinned($game: GameId!, $pinned: Boolean!) {
+  setPinned(game: $game, pinned: $pinned)
+}
+    `;
+export const ApplyUpdateDoc = gql`
+    mutation ApplyUpdate($game: GameId!) {
+  applyUpdate(game: $game)
+}
+    `;
+export const AuditLibraryDoc = gql`
+    mutation AuditLibrary {
+  auditLibrary
+}
+    `;
+export const RepairDoc = gql`
+    mutation Repair($games: [GameId!]!) {
+  repair(games: $games)
+}
+    `;
+export const MoveInInstallQueueDoc = gql`
+    mutation MoveInInstallQueue($game: GameId!, $position: Int!) {
+  moveInInstallQueue(game: $game, position: $position)
+}
+    `;
+export const RemoveFromInstallQueueDoc = gql`
+    mutation RemoveFromInstallQueue($game: GameId!) {
+  removeFromInstallQueue(game: $game)
+}
+    `;
+export const DownloadAllDoc = gql`
+    mutation DownloadAll($games: [GameId!]!) {
+  downloadAll(games: $games)
+}
+    `;
+export const SetPreflightConcurrencyDoc = gql`
+    mutation SetPreflightConcurrency($limit: Int!) {
+  setPreflightConcurrency(limit: $limit)
+}
+    `;
 export const GamesDoc = gql`
     query Games {
+  downloadsPaused
+  downloadWindowOpens
+  setupComplete
+  listView
+  verifyBeforeRun
+  advanced
+  gamesDir
+  gamesDirAvailable
+  gameListUpdated
+  skippedGames
+  installQueue
   games {
     id
     name
     icon
+    description
+    changelog
+    homepage
+    hooksError
+    runningHook
+    hookFailure
+    archiveDownloaded
+    tags
+    saveDirExists
+    saveSize
+    installDir
+    exePath
+    workingDir
+    cpuUsage
+    memoryUsage
+    pinned
+    updateHeld
+    resumedAt
+    quarantined
     status {
       status
-      progress
+      exactProgress
+      paused
+      error
+    }
+    errors {
+      time
+      stage
+      message
+    }
+  }
+}
+    `;
+export const LocaleDoc = gql`
+    query Locale {
+  locale
+}
+    `;
+export const SetupDoc = gql`
+    query Setup {
+  setupComplete
+  gamesDir
+  savesDir
+  onedriveSavesDir
+}
+    `;
+export const OrphanedBytesDoc = gql`
+    query OrphanedBytes {
+  orphanedBytes
+}
+    `;
+export const BugReportDoc = gql`
+    query BugReport($error: String) {
+  bugReport(error: $error)
+}
+    `;
+export const GameOutputDoc = gql`
+    query GameOutput($id: Int!) {
+  game(id: $id) {
+    output
+    exited
+    exitCode
+  }
+}
+    `;
+export const ArchiveCacheDoc = gql`
+    query ArchiveCache {
+  keepArchives
+  archiveCacheBytes
+  archiveCacheLimit
+}
+    `;
+export const SettingsDoc = gql`
+    query Settings {
+  downloadRetries
+  maxConcurrentWork
+  preflightConcurrency
+  stagingDir
+  customStagingDir
+}
+    `;
+export const LibraryAuditDoc = gql`
+    query LibraryAudit {
+  libraryAudit {
+    running
+    checked
+    total
+    damagedFiles
+    repairSize
+    games {
+      game
+      name
+      damagedFiles
+      repairSize
     }
   }
 }
     `;
+export const DownloadLinksDoc = gql`
+    query DownloadLinks($game: GameId!) {
+  downloadLinks(game: $game) {
+    source
+    url
+    strategy
+    error
+  }
+}
+    `;
+export const DownloadEstimateDoc = gql`
+    query DownloadEstimate($games: [GameId!]!) {
+  downloadEstimate(games: $games) {
+    totalBytes
+    unknown
+    availableBytes
+  }
+}
+    `;
 export const DeleteGame = (
             options: Omit<
               MutationOptions<any, DeleteGameMutationVariables>, 
@@ -225,47 +1282,873 @@ export const LaunchFirefox = (
             });
             return m;
           }
-export const Games = (
+export const PauseDownloads = (
             options: Omit<
-              WatchQueryOptions<GamesQueryVariables>, 
-              "query"
+              MutationOptions<any, PauseDownloadsMutationVariables>, 
+              "mutation"
             >
-          ): Readable<
-            ApolloQueryResult<GamesQuery> & {
-              query: ObservableQuery<
-                GamesQuery,
-                GamesQueryVariables
-              >;
-            }
-          > => {
-            const q = client.watchQuery({
-              query: GamesDoc,
+          ) => {
+            const m = client.mutate<PauseDownloadsMutation, PauseDownloadsMutationVariables>({
+              mutation: PauseDownloadsDoc,
               ...options,
             });
-            var result = readable<
-              ApolloQueryResult<GamesQuery> & {
-                query: ObservableQuery<
-                  GamesQuery,
-                  GamesQueryVariables
-                >;
-              }
-            >(
-              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
-              (set) => {
-                q.subscribe((v: any) => {
-                  set({ ...v, query: q });
-                });
-              }
-            );
-            return result;
+            return m;
           }
-        
-              export const AsyncGames = (
-                options: Omit<
-                  QueryOptions<GamesQueryVariables>,
-                  "query"
-                >
-              ) => {
-                return client.query<GamesQuery>({query: GamesDoc, ...options})
+export const ResumeDownloads = (
+            options: Omit<
+              MutationOptions<any, ResumeDownloadsMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<ResumeDownloadsMutation, ResumeDownloadsMutationVariables>({
+              mutation: ResumeDownloadsDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetLocale = (
+            options: Omit<
+              MutationOptions<any, SetLocaleMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetLocaleMutation, SetLocaleMutationVariables>({
+              mutation: SetLocaleDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetGamesDir = (
+            options: Omit<
+              MutationOptions<any, SetGamesDirMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetGamesDirMutation, SetGamesDirMutationVariables>({
+              mutation: SetGamesDirDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetSavesDir = (
+            options: Omit<
+              MutationOptions<any, SetSavesDirMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetSavesDirMutation, SetSavesDirMutationVariables>({
+              mutation: SetSavesDirDoc,
+              ...options,
+            });
+            return m;
+          }
+export const CompleteSetup = (
+            options: Omit<
+              MutationOptions<any, CompleteSetupMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<CompleteSetupMutation, CompleteSetupMutationVariables>({
+              mutation: CompleteSetupDoc,
+              ...options,
+            });
+            return m;
+          }
+export const CleanUp = (
+            options: Omit<
+              MutationOptions<any, CleanUpMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<CleanUpMutation, CleanUpMutationVariables>({
+              mutation: CleanUpDoc,
+              ...options,
+            });
+            return m;
+          }
+export const RerunSetup = (
+            options: Omit<
+              MutationOptions<any, RerunSetupMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<RerunSetupMutation, RerunSetupMutationVariables>({
+              mutation: RerunSetupDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetListView = (
+            options: Omit<
+              MutationOptions<any, SetListViewMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetListViewMutation, SetListViewMutationVariables>({
+              mutation: SetListViewDoc,
+              ...options,
+            });
+            return m;
+          }
+export const CancelInstall = (
+            options: Omit<
+              MutationOptions<any, CancelInstallMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<CancelInstallMutation, CancelInstallMutationVariables>({
+              mutation: CancelInstallDoc,
+              ...options,
+            });
+            return m;
+          }
+export const OpenSavesFolder = (
+            options: Omit<
+              MutationOptions<any, OpenSavesFolderMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<OpenSavesFolderMutation, OpenSavesFolderMutationVariables>({
+              mutation: OpenSavesFolderDoc,
+              ...options,
+            });
+            return m;
+          }
+export const OpenHomepage = (
+            options: Omit<
+              MutationOptions<any, OpenHomepageMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<OpenHomepageMutation, OpenHomepageMutationVariables>({
+              mutation: OpenHomepageDoc,
+              ...options,
+            });
+            return m;
+          }
+export const OpenAntivirusExclusions = (
+            options: Omit<
+              MutationOptions<any, OpenAntivirusExclusionsMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<OpenAntivirusExclusionsMutation, OpenAntivirusExclusionsMutationVariables>({
+              mutation: OpenAntivirusExclusionsDoc,
+              ...options,
+            });
+            return m;
+          }
+export const ImportArchive = (
+            options: Omit<
+              MutationOptions<any, ImportArchiveMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<ImportArchiveMutation, ImportArchiveMutationVariables>({
+              mutation: ImportArchiveDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetInstallDir = (
+            options: Omit<
+              MutationOptions<any, SetInstallDirMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetInstallDirMutation, SetInstallDirMutationVariables>({
+              mutation: SetInstallDirDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetKeepArchives = (
+            options: Omit<
+              MutationOptions<any, SetKeepArchivesMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetKeepArchivesMutation, SetKeepArchivesMutationVariables>({
+              mutation: SetKeepArchivesDoc,
+              ...options,
+            });
+            return m;
+          }
+export const ClearArchiveCache = (
+            options: Omit<
+              MutationOptions<any, ClearArchiveCacheMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<ClearArchiveCacheMutation, ClearArchiveCacheMutationVariables>({
+              mutation: ClearArchiveCacheDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetVerifyBeforeRun = (
+            options: Omit<
+              MutationOptions<any, SetVerifyBeforeRunMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetVerifyBeforeRunMutation, SetVerifyBeforeRunMutationVariables>({
+              mutation: SetVerifyBeforeRunDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetArchiveCacheLimit = (
+            options: Omit<
+              MutationOptions<any, SetArchiveCacheLimitMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetArchiveCacheLimitMutation, SetArchiveCacheLimitMutationVariables>({
+              mutation: SetArchiveCacheLimitDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetAdvanced = (
+            options: Omit<
+              MutationOptions<any, SetAdvancedMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetAdvancedMutation, SetAdvancedMutationVariables>({
+              mutation: SetAdvancedDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetDownloadRetries = (
+            options: Omit<
+              MutationOptions<any, SetDownloadRetriesMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetDownloadRetriesMutation, SetDownloadRetriesMutationVariables>({
+              mutation: SetDownloadRetriesDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetMaxConcurrentWork = (
+            options: Omit<
+              MutationOptions<any, SetMaxConcurrentWorkMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetMaxConcurrentWorkMutation, SetMaxConcurrentWorkMutationVariables>({
+              mutation: SetMaxConcurrentWorkDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetStagingDir = (
+            options: Omit<
+              MutationOptions<any, SetStagingDirMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetStagingDirMutation, SetStagingDirMutationVariables>({
+              mutation: SetStagingDirDoc,
+              ...options,
+            });
+            return m;
+          }
+export const ReinstallGame = (
+            options: Omit<
+              MutationOptions<any, ReinstallGameMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<ReinstallGameMutation, ReinstallGameMutationVariables>({
+              mutation: ReinstallGameDoc,
+              ...options,
+            });
+            return m;
+          }
+export const ClearErrors = (
+            options: Omit<
+              MutationOptions<any, ClearErrorsMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<ClearErrorsMutation, ClearErrorsMutationVariables>({
+              mutation: ClearErrorsDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetPinned = (
+            options: Omit<
+              MutationOptions<any, SetPinnedMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetPinnedMutation, SetPinnedMutationVariables>({
+              mutation: SetPinnedDoc,
+              ...options,
+            });
+            return m;
+          }
+export const ApplyUpdate = (
+            options: Omit<
+              MutationOptions<any, ApplyUpdateMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<ApplyUpdateMutation, ApplyUpdateMutationVariables>({
+              mutation: ApplyUpdateDoc,
+              ...options,
+            });
+            return m;
+          }
+export const AuditLibrary = (
+            options: Omit<
+              MutationOptions<any, AuditLibraryMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<AuditLibraryMutation, AuditLibraryMutationVariables>({
+              mutation: AuditLibraryDoc,
+              ...options,
+            });
+            return m;
+          }
+export const Repair = (
+            options: Omit<
+              MutationOptions<any, RepairMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<RepairMutation, RepairMutationVariables>({
+              mutation: RepairDoc,
+              ...options,
+            });
+            return m;
+          }
+export const MoveInInstallQueue = (
+            options: Omit<
+              MutationOptions<any, MoveInInstallQueueMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<MoveInInstallQueueMutation, MoveInInstallQueueMutationVariables>({
+              mutation: MoveInInstallQueueDoc,
+              ...options,
+            });
+            return m;
+          }
+export const RemoveFromInstallQueue = (
+            options: Omit<
+              MutationOptions<any, RemoveFromInstallQueueMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<RemoveFromInstallQueueMutation, RemoveFromInstallQueueMutationVariables>({
+              mutation: RemoveFromInstallQueueDoc,
+              ...options,
+            });
+            return m;
+          }
+export const DownloadAll = (
+            options: Omit<
+              MutationOptions<any, DownloadAllMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<DownloadAllMutation, DownloadAllMutationVariables>({
+              mutation: DownloadAllDoc,
+              ...options,
+            });
+            return m;
+          }
+export const SetPreflightConcurrency = (
+            options: Omit<
+              MutationOptions<any, SetPreflightConcurrencyMutationVariables>, 
+              "mutation"
+            >
+          ) => {
+            const m = client.mutate<SetPreflightConcurrencyMutation, SetPreflightConcurrencyMutationVariables>({
+              mutation: SetPreflightConcurrencyDoc,
+              ...options,
+            });
+            return m;
+          }
+export const Games = (
+            options: Omit<
+              WatchQueryOptions<GamesQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<GamesQuery> & {
+              query: ObservableQuery<
+                GamesQuery,
+                GamesQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: GamesDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<GamesQuery> & {
+                query: ObservableQuery<
+                  GamesQuery,
+                  GamesQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncGames = (
+                options: Omit<
+                  QueryOptions<GamesQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<GamesQuery>({query: GamesDoc, ...options})
+              }
+export const Locale = (
+            options: Omit<
+              WatchQueryOptions<LocaleQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<LocaleQuery> & {
+              query: ObservableQuery<
+                LocaleQuery,
+                LocaleQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: LocaleDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<LocaleQuery> & {
+                query: ObservableQuery<
+                  LocaleQuery,
+                  LocaleQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncLocale = (
+                options: Omit<
+                  QueryOptions<LocaleQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<LocaleQuery>({query: LocaleDoc, ...options})
+              }
+export const Setup = (
+            options: Omit<
+              WatchQueryOptions<SetupQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<SetupQuery> & {
+              query: ObservableQuery<
+                SetupQuery,
+                SetupQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: SetupDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<SetupQuery> & {
+                query: ObservableQuery<
+                  SetupQuery,
+                  SetupQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncSetup = (
+                options: Omit<
+                  QueryOptions<SetupQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<SetupQuery>({query: SetupDoc, ...options})
+              }
+export const OrphanedBytes = (
+            options: Omit<
+              WatchQueryOptions<OrphanedBytesQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<OrphanedBytesQuery> & {
+              query: ObservableQuery<
+                OrphanedBytesQuery,
+                OrphanedBytesQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: OrphanedBytesDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<OrphanedBytesQuery> & {
+                query: ObservableQuery<
+                  OrphanedBytesQuery,
+                  OrphanedBytesQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncOrphanedBytes = (
+                options: Omit<
+                  QueryOptions<OrphanedBytesQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<OrphanedBytesQuery>({query: OrphanedBytesDoc, ...options})
+              }
+export const BugReport = (
+            options: Omit<
+              WatchQueryOptions<BugReportQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<BugReportQuery> & {
+              query: ObservableQuery<
+                BugReportQuery,
+                BugReportQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: BugReportDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<BugReportQuery> & {
+                query: ObservableQuery<
+                  BugReportQuery,
+                  BugReportQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncBugReport = (
+                options: Omit<
+                  QueryOptions<BugReportQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<BugReportQuery>({query: BugReportDoc, ...options})
+              }
+export const GameOutput = (
+            options: Omit<
+              WatchQueryOptions<GameOutputQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<GameOutputQuery> & {
+              query: ObservableQuery<
+                GameOutputQuery,
+                GameOutputQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: GameOutputDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<GameOutputQuery> & {
+                query: ObservableQuery<
+                  GameOutputQuery,
+                  GameOutputQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncGameOutput = (
+                options: Omit<
+                  QueryOptions<GameOutputQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<GameOutputQuery>({query: GameOutputDoc, ...options})
+              }
+export const ArchiveCache = (
+            options: Omit<
+              WatchQueryOptions<ArchiveCacheQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<ArchiveCacheQuery> & {
+              query: ObservableQuery<
+                ArchiveCacheQuery,
+                ArchiveCacheQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: ArchiveCacheDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<ArchiveCacheQuery> & {
+                query: ObservableQuery<
+                  ArchiveCacheQuery,
+                  ArchiveCacheQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncArchiveCache = (
+                options: Omit<
+                  QueryOptions<ArchiveCacheQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<ArchiveCacheQuery>({query: ArchiveCacheDoc, ...options})
+              }
+export const Settings = (
+            options: Omit<
+              WatchQueryOptions<SettingsQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<SettingsQuery> & {
+              query: ObservableQuery<
+                SettingsQuery,
+                SettingsQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: SettingsDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<SettingsQuery> & {
+                query: ObservableQuery<
+                  SettingsQuery,
+                  SettingsQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncSettings = (
+                options: Omit<
+                  QueryOptions<SettingsQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<SettingsQuery>({query: SettingsDoc, ...options})
+              }
+export const LibraryAudit = (
+            options: Omit<
+              WatchQueryOptions<LibraryAuditQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<LibraryAuditQuery> & {
+              query: ObservableQuery<
+                LibraryAuditQuery,
+                LibraryAuditQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: LibraryAuditDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<LibraryAuditQuery> & {
+                query: ObservableQuery<
+                  LibraryAuditQuery,
+                  LibraryAuditQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncLibraryAudit = (
+                options: Omit<
+                  QueryOptions<LibraryAuditQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<LibraryAuditQuery>({query: LibraryAuditDoc, ...options})
+              }
+export const DownloadLinks = (
+            options: Omit<
+              WatchQueryOptions<DownloadLinksQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<DownloadLinksQuery> & {
+              query: ObservableQuery<
+                DownloadLinksQuery,
+                DownloadLinksQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: DownloadLinksDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<DownloadLinksQuery> & {
+                query: ObservableQuery<
+                  DownloadLinksQuery,
+                  DownloadLinksQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncDownloadLinks = (
+                options: Omit<
+                  QueryOptions<DownloadLinksQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<DownloadLinksQuery>({query: DownloadLinksDoc, ...options})
+              }
+export const DownloadEstimate = (
+            options: Omit<
+              WatchQueryOptions<DownloadEstimateQueryVariables>, 
+              "query"
+            >
+          ): Readable<
+            ApolloQueryResult<DownloadEstimateQuery> & {
+              query: ObservableQuery<
+                DownloadEstimateQuery,
+                DownloadEstimateQueryVariables
+              >;
+            }
+          > => {
+            const q = client.watchQuery({
+              query: DownloadEstimateDoc,
+              ...options,
+            });
+            var result = readable<
+              ApolloQueryResult<DownloadEstimateQuery> & {
+                query: ObservableQuery<
+                  DownloadEstimateQuery,
+                  DownloadEstimateQueryVariables
+                >;
+              }
+            >(
+              { data: {} as any, loading: true, error: undefined, networkStatus: 1, query: q },
+              (set) => {
+                q.subscribe((v: any) => {
+                  set({ ...v, query: q });
+                });
+              }
+            );
+            return result;
+          }
+        
+              export const AsyncDownloadEstimate = (
+                options: Omit<
+                  QueryOptions<DownloadEstimateQueryVariables>,
+                  "query"
+                >
+              ) => {
+                return client.query<DownloadEstimateQuery>({query: DownloadEstimateDoc, ...options})
               }
             
\ No newline at end of file
@@ -0,0 +1,327 @@
+import { derived, writable } from 'svelte/store';
+
+const en = {
+	loading: 'Loading...',
+	launchBrowser: 'Launch Unblocked Webbrowser',
+	pauseAll: 'Pause All Downloads',
+	resumeAll: 'Resume All Downloads',
+	download: 'Download',
+	retryInstall: 'Retry Install',
+	play: 'Play',
+	running: 'Running...',
+	paused: 'Paused',
+	broken: 'Broken',
+	failed: 'Failed',
+	retry: 'Retry',
+	rerunSetup: 'Re-run setup',
+	rerunSetupHelp: "Apply the game's latest setup steps without downloading it again",
+	setupUpToDate: 'Setup is already up to date',
+	rerunAnyway: 'Run it anyway',
+	reinstall: 'Reinstall',
+	reinstallHelp: "Delete the game's files and download it again. Saves are kept.",
+	confirmReinstall: "Delete the game's files? Saves are kept.",
+	bytes: 'bytes',
+	files: 'files',
+	details: 'Details',
+	whatsNew: "What's new",
+	homepage: 'Help & controls',
+	gamesDirUnavailable: "Your games folder isn't available (removed drive?) — pick a new location.",
+	newGamesDir: 'New games folder, e.g. D:\\Games',
+	useFolder: 'Use this folder',
+	wizardWelcome: "Welcome to Bramlett's Games!",
+	wizardFolders: 'Folders',
+	wizardFoldersHelp:
+		'Pick where games are installed and where their saves are kept. The defaults are fine for most people.',
+	gamesFolder: 'Games folder',
+	savesFolder: 'Saves folder',
+	useOneDrive: 'Use OneDrive',
+	useOneDriveHelp: 'Keep saves in OneDrive so they follow you to other computers',
+	wizardGames: 'Get the game list',
+	wizardGamesHelp: 'Fetch the list of games you can download.',
+	fetchGames: 'Fetch games',
+	wizardDone: 'All set',
+	wizardHowTo:
+		'Press Download on a game to download and install it, then press Play once it has finished. Your saves are kept in the saves folder.',
+	next: 'Next',
+	finish: 'Start playing',
+	cleanUp: 'Clean Up',
+	confirmCleanUp: 'Remove leftover downloads and failed installs?',
+	remove: 'Remove',
+	cancel: 'Cancel',
+	reclaimed: 'Freed',
+	nothingToClean: 'Nothing to clean up.',
+	copyLogs: 'Copy Logs',
+	copyLogsHelp: 'Copies a bug report with the recent logs, to paste into a message to the maintainers',
+	copied: 'Copied!',
+	listView: 'List View',
+	gridView: 'Grid View',
+	untagged: 'untagged',
+	saves: 'Saves',
+	notCreated: 'not created',
+	openSaves: 'Open Saves Folder',
+	importArchive: 'Install from a file',
+	importArchiveHelp: "Install from the game's .7z file if you already have it, like on a USB drive",
+	archivePath: 'Path to the .7z file',
+	install: 'Install',
+	location: 'Location',
+	changeLocation: 'Change Location',
+	move: 'Move',
+	useGamesFolder: 'Use Games Folder',
+	console: 'Console',
+	noOutput: "The game hasn't printed anything",
+	exitedWith: 'Exited with code',
+	gameListUpdated: 'Game list updated',
+	gameListNeverUpdated: "The game list hasn't been updated — the server might be down",
+	refreshGameList: 'Refresh',
+	refreshGameListHelp: 'Check the server for new games now (F5)',
+	newGames: 'new games',
+	noNewGames: 'No new games',
+	keepArchives: 'Keep downloads',
+	keepArchivesHelp:
+		"Keeps each game's downloaded file after installing it, so reinstalling doesn't download it again",
+	clearArchiveCache: 'Clear kept downloads',
+	verifyBeforeRun: 'Check files before playing',
+	verifyBeforeRunHelp:
+		"Makes sure a game's files are all there before running it, so a game missing files can be reinstalled instead of crashing",
+	archiveCacheLimit: 'Keep up to',
+	archiveCacheLimitHelp:
+		'When kept downloads take up more than this, the ones used least recently are deleted',
+	noLimit: 'No limit',
+	runningSetup: 'Running setup...',
+	setupFailed: 'Setup failed',
+	advanced: 'Advanced',
+	advancedHelp: "Show details most people don't need, like how each game is launched",
+	launches: 'Runs',
+	launchesIn: 'in',
+	cpu: 'CPU',
+	usageHelp: 'What the game and any programs it started are using',
+	simultaneousDownloads: 'Simultaneous downloads',
+	simultaneousDownloadsHelp: 'How many games can download or install at once',
+	downloadRetries: 'Retries',
+	downloadRetriesHelp: 'How many times a download that fails partway is tried again',
+	recentErrors: 'Recent errors',
+	clearErrors: 'Clear',
+	downloadStage: 'Download',
+	installStage: 'Install',
+	runStage: 'Run',
+	pinned: 'Pinned',
+	pinVersion: 'Pin this version',
+	unpinVersion: 'Unpin',
+	pinHelp: "Keep this version when the game list changes, so a broken update doesn't replace it",
+	updateHeld: 'A newer version is available',
+	updateAnyway: 'Update anyway',
+	gamesSkipped: "games in the list couldn't be read",
+	auditLibrary: 'Audit library',
+	auditLibraryHelp: 'Check every installed game for missing or damaged files, like after an antivirus scan',
+	auditing: 'Checking games',
+	damagedFiles: 'missing or damaged files',
+	repairDownload: 'repairing downloads about',
+	repairSelected: 'Repair selected',
+	repairing: 'Games being repaired:',
+	libraryIntact: 'Every installed game is intact',
+	downloadFolder: 'Download folder',
+	downloadFolderHelp:
+		'Where games are downloaded before they are installed. Pick another drive if this one is short on space.',
+	copyDownloadLink: 'Copy download link',
+	copyDownloadLinkHelp:
+		"Finds where the game is really downloaded from, without downloading it, to tell whether the host's link is dead",
+	directLink: 'direct link',
+	driveUnconfirmed: 'Google Drive, no confirmation page',
+	driveFormAction: "Google Drive confirmation page's form",
+	driveHiddenInputs: "Google Drive confirmation page's hidden fields",
+	installQueue: 'Waiting to download',
+	installQueueHelp:
+		'Games download in this order, a few at a time. The queue is kept if the app is closed.',
+	moveUp: 'Move up',
+	moveDown: 'Move down',
+	removeFromQueue: 'Remove from queue',
+	installAll: 'Install all',
+	installAllHelp:
+		"Download every game shown that isn't installed, after checking how much that is",
+	confirmInstallAll: 'Download',
+	games: 'games',
+	unknownSize: 'of unknown size',
+	free: 'free',
+	gamesInstalling: 'games are being installed',
+	simultaneousSizeChecks: 'Simultaneous size checks',
+	simultaneousSizeChecksHelp:
+		'How many games are asked for their size at once before installing them all',
+	resumedAt: 'resumed at',
+	thisSession: 'this session',
+	scheduledFor: 'Scheduled for',
+	quarantined:
+		'Your antivirus may have removed this game — add an exclusion for the games folder',
+	addExclusion: 'Add exclusion'
+};
+
+export type Strings = typeof en;
+
+const es: Partial<Strings> = {
+	loading: 'Cargando...',
+	launchBrowser: 'Abrir navegador desbloqueado',
+	pauseAll: 'Pausar todas las descargas',
+	resumeAll: 'Reanudar todas las descargas',
+	download: 'Descargar',
+	retryInstall: 'Reintentar instalación',
+	play: 'Jugar',
+	running: 'Ejecutando...',
+	paused: 'En pausa',
+	broken: 'Dañado',
+	failed: 'Falló',
+	retry: 'Reintentar',
+	rerunSetup: 'Repetir configuración',
+	rerunSetupHelp: 'Aplica los últimos pasos de configuración del juego sin volver a descargarlo',
+	setupUpToDate: 'La configuración ya está al día',
+	rerunAnyway: 'Repetirla de todos modos',
+	reinstall: 'Reinstalar',
+	reinstallHelp: 'Borra los archivos del juego y lo vuelve a descargar. Las partidas se conservan.',
+	confirmReinstall: '¿Borrar los archivos del juego? Las partidas se conservan.',
+	bytes: 'bytes',
+	files: 'archivos',
+	details: 'Detalles',
+	whatsNew: 'Novedades',
+	homepage: 'Ayuda y controles',
+	gamesDirUnavailable:
+		'Tu carpeta de juegos no está disponible (¿se quitó la unidad?). Elige una nueva ubicación.',
+	newGamesDir: 'Nueva carpeta de juegos, p. ej. D:\\Games',
+	useFolder: 'Usar esta carpeta',
+	wizardWelcome: "¡Bienvenido a Bramlett's Games!",
+	wizardFolders: 'Carpetas',
+	wizardFoldersHelp:
+		'Elige dónde se instalan los juegos y dónde se guardan sus partidas. Los valores predeterminados sirven para la mayoría.',
+	gamesFolder: 'Carpeta de juegos',
+	savesFolder: 'Carpeta de partidas',
+	useOneDrive: 'Usar OneDrive',
+	useOneDriveHelp: 'Guarda las partidas en OneDrive para tenerlas en otras computadoras',
+	wizardGames: 'Obtener la lista de juegos',
+	wizardGamesHelp: 'Descarga la lista de juegos disponibles.',
+	fetchGames: 'Obtener juegos',
+	wizardDone: 'Todo listo',
+	wizardHowTo:
+		'Pulsa Descargar en un juego para descargarlo e instalarlo y luego pulsa Jugar cuando termine. Tus partidas se guardan en la carpeta de partidas.',
+	next: 'Siguiente',
+	finish: 'Empezar a jugar',
+	cleanUp: 'Limpiar',
+	confirmCleanUp: '¿Eliminar descargas sobrantes e instalaciones fallidas?',
+	remove: 'Eliminar',
+	cancel: 'Cancelar',
+	reclaimed: 'Liberados',
+	nothingToClean: 'No hay nada que limpiar.',
+	copyLogs: 'Copiar registros',
+	copyLogsHelp:
+		'Copia un informe de errores con los registros recientes, para pegarlo en un mensaje a los responsables',
+	copied: '¡Copiado!',
+	listView: 'Vista de lista',
+	gridView: 'Vista de cuadrícula',
+	untagged: 'sin etiqueta',
+	saves: 'Partidas',
+	notCreated: 'no creada',
+	openSaves: 'Abrir carpeta de partidas',
+	importArchive: 'Instalar desde un archivo',
+	importArchiveHelp:
+		'Instala desde el archivo .7z del juego si ya lo tienes, por ejemplo en una memoria USB',
+	archivePath: 'Ruta al archivo .7z',
+	install: 'Instalar',
+	location: 'Ubicación',
+	changeLocation: 'Cambiar ubicación',
+	move: 'Mover',
+	useGamesFolder: 'Usar carpeta de juegos',
+	console: 'Consola',
+	noOutput: 'El juego no ha mostrado nada',
+	exitedWith: 'Terminó con el código',
+	gameListUpdated: 'Lista de juegos actualizada',
+	refreshGameList: 'Actualizar',
+	refreshGameListHelp: 'Busca juegos nuevos en el servidor ahora (F5)',
+	newGames: 'juegos nuevos',
+	noNewGames: 'No hay juegos nuevos',
+	gameListNeverUpdated:
+		'La lista de juegos no se ha actualizado; puede que el servidor no esté disponible',
+	keepArchives: 'Conservar descargas',
+	keepArchivesHelp:
+		'Conserva el archivo descargado de cada juego después de instalarlo, para no tener que descargarlo otra vez al reinstalarlo',
+	clearArchiveCache: 'Borrar descargas conservadas',
+	verifyBeforeRun: 'Comprobar archivos antes de jugar',
+	verifyBeforeRunHelp:
+		'Comprueba que estén todos los archivos de un juego antes de ejecutarlo, para poder reinstalarlo en vez de que falle',
+	archiveCacheLimit: 'Conservar hasta',
+	archiveCacheLimitHelp:
+		'Cuando las descargas conservadas ocupan más que esto, se borran las que se usaron hace más tiempo',
+	noLimit: 'Sin límite',
+	runningSetup: 'Configurando...',
+	setupFailed: 'Falló la configuración',
+	advanced: 'Avanzado',
+	advancedHelp:
+		'Muestra detalles que la mayoría no necesita, como la forma en que se inicia cada juego',
+	launches: 'Ejecuta',
+	launchesIn: 'en',
+	cpu: 'CPU',
+	usageHelp: 'Lo que usan el juego y los programas que inició',
+	simultaneousDownloads: 'Descargas simultáneas',
+	simultaneousDownloadsHelp: 'Cuántos juegos se pueden descargar o instalar a la vez',
+	downloadRetries: 'Reintentos',
+	downloadRetriesHelp: 'Cuántas veces se vuelve a intentar una descarga que falla a medias',
+	recentErrors: 'Errores recientes',
+	clearErrors: 'Borrar',
+	downloadStage: 'Descarga',
+	installStage: 'Instalación',
+	runStage: 'Ejecución',
+	pinned: 'Fijado',
+	pinVersion: 'Fijar esta versión',
+	unpinVersion: 'Dejar de fijar',
+	pinHelp: 'Mantiene esta versión cuando cambia la lista de juegos, para que una actualización rota no la reemplace',
+	updateHeld: 'Hay una versión más nueva',
+	updateAnyway: 'Actualizar de todos modos',
+	gamesSkipped: 'juegos de la lista no se pudieron leer',
+	auditLibrary: 'Revisar biblioteca',
+	auditLibraryHelp: 'Busca archivos que falten o estén dañados en cada juego instalado, por ejemplo después de un análisis del antivirus',
+	auditing: 'Revisando juegos',
+	damagedFiles: 'archivos que faltan o están dañados',
+	repairDownload: 'repararlos descarga unos',
+	repairSelected: 'Reparar seleccionados',
+	repairing: 'Juegos reparándose:',
+	libraryIntact: 'Todos los juegos instalados están intactos',
+	downloadFolder: 'Carpeta de descargas',
+	downloadFolderHelp:
+		'Dónde se descargan los juegos antes de instalarlos. Elige otra unidad si esta tiene poco espacio.',
+	copyDownloadLink: 'Copiar enlace de descarga',
+	copyDownloadLinkHelp:
+		'Busca desde dónde se descarga realmente el juego, sin descargarlo, para saber si el enlace del servidor ya no funciona',
+	directLink: 'enlace directo',
+	driveUnconfirmed: 'Google Drive, sin página de confirmación',
+	driveFormAction: 'formulario de la página de confirmación de Google Drive',
+	driveHiddenInputs: 'campos ocultos de la página de confirmación de Google Drive',
+	installQueue: 'Esperando para descargar',
+	installQueueHelp:
+		'Los juegos se descargan en este orden, unos pocos a la vez. La cola se guarda si se cierra la aplicación.',
+	moveUp: 'Subir',
+	moveDown: 'Bajar',
+	removeFromQueue: 'Quitar de la cola',
+	installAll: 'Instalar todos',
+	installAllHelp:
+		'Descargar todos los juegos mostrados que no están instalados, después de ver cuánto ocupan',
+	confirmInstallAll: 'Descargar',
+	games: 'juegos',
+	unknownSize: 'de tamaño desconocido',
+	free: 'libres',
+	gamesInstalling: 'juegos se están instalando',
+	simultaneousSizeChecks: 'Comprobaciones de tamaño simultáneas',
+	simultaneousSizeChecksHelp:
+		'Cuántos juegos se consultan a la vez por su tamaño antes de instalarlos todos',
+	resumedAt: 'reanudado en',
+	thisSession: 'en esta sesión',
+	scheduledFor: 'Programado para las',
+	quarantined:
+		'Es posible que tu antivirus haya eliminado este juego — agrega una exclusión para la carpeta de juegos',
+	addExclusion: 'Agregar exclusión'
+};
+
+const tables: Record<string, Partial<Strings>> = { en, es };
+
+/** The UI language, e.g. `es-MX`. Only the language part is used to pick strings. */
+export const locale = writable('en');
+
+/** The UI strings for the current locale. Missing translations fall back to English. */
+export const t = derived(locale, ($locale) => {
+	const language = $locale.split(/[-_]/)[0].toLowerCase();
+	return { ...en, ...tables[language] };
+});
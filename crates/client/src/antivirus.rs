@@ -0,0 +1,53 @@
+//! Noticing when an antivirus has taken a game's executable away.
+//!
+//! Windows Defender often quarantines a game's executable right after it's extracted, so it's
+//! gone (or can't be opened) by the time the game is run. That's reported as
+//! [`ClientError::ExeQuarantined`] instead of a plain I/O error, so the user can be told to add an
+//! exclusion for the games folder.
+
+use std::{fs::File, io, path::Path};
+
+use crate::{ClientError, Result};
+
+/// Windows Security's virus & threat protection settings, where exclusions are added.
+pub const EXCLUSIONS_PAGE: &str = "windowsdefender://threatsettings";
+
+/// Whether an I/O error means an antivirus blocked the file.
+pub fn is_blocked(e: &io::Error) -> bool {
+    // ERROR_VIRUS_INFECTED and ERROR_VIRUS_DELETED on windows
+    let codes: &[i32] = if cfg!(windows) { &[225, 226] } else { &[] };
+    e.raw_os_error().is_some_and(|code| codes.contains(&code))
+}
+
+/// Makes sure `exe` is still there and can be opened. Opening it is what makes an antivirus
+/// scan it, so a file it's about to quarantine fails here instead of partway through starting.
+///
+/// # Errors
+/// Returns [`ClientError::ExeQuarantined`] if it's missing or blocked, or another error if it
+/// can't be opened for some other reason.
+pub fn check_exe(exe: &Path) -> Result<()> {
+    match File::open(exe) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound || is_blocked(&e) => {
+            Err(ClientError::ExeQuarantined(exe.to_path_buf()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Opens [`EXCLUSIONS_PAGE`] in Windows Security.
+///
+/// # Errors
+/// Returns an error if it can't be opened, or if this isn't Windows.
+pub fn open_exclusions() -> io::Result<()> {
+    if !cfg!(windows) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Windows Security is only on Windows",
+        ));
+    }
+    std::process::Command::new("explorer")
+        .arg(EXCLUSIONS_PAGE)
+        .spawn()?;
+    Ok(())
+}
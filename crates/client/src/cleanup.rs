@@ -0,0 +1,330 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use common::{GameId, GameInfo};
+
+use crate::{ClientError, Config, GameStatus, Result};
+
+/// Finds leftovers in the games and [staging](Config::staging_dir) directories that don't belong
+/// to an installed game.
+///
+/// These are archives and partial downloads that can't be used to install a game, directories of games that
+/// aren't installed, and empty directories. Anything else (like files the user put there) is left alone.
+pub fn find_orphans(config: &Config) -> Vec<PathBuf> {
+    let games_dir = config.games_dir();
+    let staging_dir = config.staging_dir();
+    let mut dirs = vec![games_dir.clone()];
+    if staging_dir != games_dir {
+        dirs.push(staging_dir.clone());
+    }
+    let entries = dirs
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten();
+    let games = config.games();
+
+    let mut orphans = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+
+        let id = name
+            .strip_suffix(".7z.part")
+            .or_else(|| name.strip_suffix(".7z"))
+            // left by an install that was extracted as it downloaded
+            .or_else(|| name.strip_suffix(".extracting"))
+            .or_else(|| is_dir.then_some(name))
+            .and_then(|id| id.parse().ok())
+            .map(GameId);
+        let orphaned = match id.map(|id| games.get(&id)) {
+            Some(Some(game)) => match game.status {
+                // in use
+                GameStatus::Downloading(_) | GameStatus::Installing(_) => false,
+                // kept so the download can be resumed
+                GameStatus::NotDownloaded | GameStatus::Failed(_)
+                    if game.partial.as_ref().is_some_and(|p| p.path == path) =>
+                {
+                    false
+                }
+                // kept so the install can be retried without downloading it again
+                GameStatus::NotDownloaded | GameStatus::Failed(_)
+                    if path == config.archive_path(game.info.id) =>
+                {
+                    false
+                }
+                GameStatus::NotDownloaded | GameStatus::Failed(_) => true,
+                // a valid install, unless the game has since been moved somewhere else
+                GameStatus::Ready | GameStatus::Running => {
+                    !is_dir || path != config.game_dir(game.info.id)
+                }
+            },
+            // a game that isn't in the library anymore
+            Some(None) => true,
+            // where downloads go, even while there aren't any
+            None => is_dir && path != staging_dir && is_empty_dir(&path),
+        };
+        if orphaned {
+            orphans.push(path);
+        }
+    }
+    orphans
+}
+
+/// Removes everything [`find_orphans`] finds, returning how many bytes were freed.
+///
+/// # Errors
+/// Returns an error if a file or directory can't be removed.
+pub fn remove_orphans(config: &Config) -> Result<u64> {
+    let mut reclaimed = 0;
+    for path in find_orphans(config) {
+        let size = size_of(&path);
+        tracing::info!("removing {path:?} ({size} bytes)");
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+        reclaimed += size;
+    }
+    Ok(reclaimed)
+}
+
+/// The total size of a file, or of every file in a directory.
+pub fn size_of(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    std::fs::read_dir(path).map_or(0, |entries| {
+        entries.flatten().map(|e| size_of(&e.path())).sum()
+    })
+}
+
+/// Removes a directory and everything in it, trying again a few times if a file is locked.
+///
+/// Antivirus software often holds newly written files open for a moment to scan them, which makes
+/// deleting them fail on Windows.
+pub async fn remove_dir_all_retrying(path: &Path) -> std::io::Result<()> {
+    const ATTEMPTS: u32 = 5;
+    let mut attempt = 1;
+    loop {
+        match tokio::fs::remove_dir_all(path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) if is_locked(&e) && attempt < ATTEMPTS => {
+                tracing::debug!("{path:?} is locked ({e}); trying again");
+                tokio::time::sleep(Duration::from_millis(500) * attempt).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Moves a directory, copying it and removing the original if it's going to another drive.
+///
+/// # Errors
+/// Returns an error if `to` already exists or anything can't be copied. A partial copy is
+/// removed, leaving the original where it was.
+pub fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match std::fs::rename(from, to) {
+        // EXDEV on unix, ERROR_NOT_SAME_DEVICE on windows
+        Err(e) if e.raw_os_error() == Some(if cfg!(windows) { 17 } else { 18 }) => {
+            tracing::debug!("{to:?} is on another drive; copying {from:?}");
+            if let Err(e) = copy_dir(from, to) {
+                let _ = std::fs::remove_dir_all(to);
+                return Err(e);
+            }
+            std::fs::remove_dir_all(from)
+        }
+        res => res,
+    }
+}
+
+/// Moves a file, copying it and removing the original if it's going to another drive.
+///
+/// # Errors
+/// Returns an error if it can't be copied. A partial copy is removed, leaving the original where
+/// it was.
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(e) if e.raw_os_error() == Some(if cfg!(windows) { 17 } else { 18 }) => {
+            tracing::debug!("{to:?} is on another drive; copying {from:?}");
+            if let Err(e) = std::fs::copy(from, to) {
+                let _ = std::fs::remove_file(to);
+                return Err(e);
+            }
+            std::fs::remove_file(from)
+        }
+        res => res,
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            std::fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether an I/O error means a file is being used by another program.
+fn is_locked(e: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION on windows
+    e.kind() == std::io::ErrorKind::PermissionDenied
+        || (cfg!(windows) && matches!(e.raw_os_error(), Some(32 | 33)))
+}
+
+/// Removes the files matching a game's [`GameInfo::cleanup_globs`] after it's been run. Errors
+/// are logged rather than returned since the game has already stopped.
+#[allow(clippy::option_if_let_else)]
+pub fn clean_up_after_run(config: &Config, info: &GameInfo) {
+    let game_dir = config.game_dir(info.id);
+    for pattern in &info.cleanup_globs {
+        let res = match pattern.strip_prefix("$TEMP/") {
+            Some(relative) => remove_temp_glob(&std::env::temp_dir(), relative),
+            None => remove_glob(&game_dir, pattern),
+        };
+        match res {
+            Ok(0) => {}
+            Ok(n) => tracing::info!("removed {n} files matching {pattern:?} for {}", info.name),
+            Err(e) => tracing::warn!("failed to clean up {pattern:?} for {}: {e}", info.name),
+        }
+    }
+}
+
+/// Removes the files under `dir` whose paths relative to it match `pattern` (see
+/// [`GameInfo::cleanup_globs`]), returning how many were removed.
+///
+/// Symlinks are removed rather than followed, so nothing outside `dir` is ever touched.
+///
+/// # Errors
+/// Returns [`ClientError::InvalidGlob`] if the pattern is absolute or contains `..`, or an error
+/// if a matching file can't be removed.
+pub fn remove_glob(dir: &Path, pattern: &str) -> Result<usize> {
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    if pattern
+        .first()
+        .is_some_and(|c| c.is_empty() || c.contains(':'))
+        || pattern.contains(&"..")
+    {
+        return Err(ClientError::InvalidGlob(pattern.join("/")));
+    }
+
+    let mut files = Vec::new();
+    list_files(dir, &mut Vec::new(), &mut files);
+    let mut removed = 0;
+    for (path, key) in files {
+        if glob_matches(&pattern, &key) {
+            tracing::debug!("removing {path:?}");
+            std::fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Removes the files in the temp directory matching a `$TEMP/` pattern (with the `$TEMP/`
+/// stripped), returning how many were removed. The temp directory is shared with every other
+/// program, so the pattern has to start with the name of the game's own file or folder there,
+/// like `MyGame/*.log`.
+///
+/// # Errors
+/// Returns [`ClientError::InvalidGlob`] if the pattern's first component has wildcards, or any
+/// error [`remove_glob`] would.
+pub fn remove_temp_glob(temp_dir: &Path, pattern: &str) -> Result<usize> {
+    let (first, rest) = pattern.split_once('/').unwrap_or((pattern, ""));
+    if first.is_empty() || first == ".." || first.contains(['*', '?', ':']) {
+        return Err(ClientError::InvalidGlob(format!("$TEMP/{pattern}")));
+    }
+    let path = temp_dir.join(first);
+    let Ok(meta) = std::fs::symlink_metadata(&path) else {
+        return Ok(0);
+    };
+    if rest.is_empty() {
+        if meta.is_dir() {
+            return Ok(0);
+        }
+        tracing::debug!("removing {path:?}");
+        std::fs::remove_file(&path)?;
+        return Ok(1);
+    }
+    // a symlink isn't followed, like in `remove_glob`
+    if !meta.is_dir() {
+        return Ok(0);
+    }
+    remove_glob(&path, rest)
+}
+
+/// Lists every file under `dir` along with its path components relative to it, without following
+/// symlinks.
+fn list_files(dir: &Path, prefix: &mut Vec<String>, files: &mut Vec<(PathBuf, Vec<String>)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        prefix.push(name);
+        // `DirEntry::file_type` doesn't follow symlinks
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            list_files(&entry.path(), prefix, files);
+        } else {
+            files.push((entry.path(), prefix.clone()));
+        }
+        prefix.pop();
+    }
+}
+
+/// Whether a path's components match a pattern's, where a `**` component matches any number of
+/// components.
+fn glob_matches(pattern: &[&str], path: &[String]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, None) => true,
+        (Some((&"**", rest)), _) => {
+            glob_matches(rest, path) || (!path.is_empty() && glob_matches(pattern, &path[1..]))
+        }
+        (Some((pattern_component, rest)), Some((component, path_rest))) => {
+            let pattern_chars = pattern_component.chars().collect::<Vec<_>>();
+            let chars = component.chars().collect::<Vec<_>>();
+            component_matches(&pattern_chars, &chars) && glob_matches(rest, path_rest)
+        }
+        _ => false,
+    }
+}
+
+/// Whether a single path component matches a pattern with `*` and `?` wildcards.
+fn component_matches(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            component_matches(rest, name)
+                || (!name.is_empty() && component_matches(pattern, &name[1..]))
+        }
+        (Some(('?', rest)), Some((_, name_rest))) => component_matches(rest, name_rest),
+        (Some((p, rest)), Some((c, name_rest))) => p == c && component_matches(rest, name_rest),
+        _ => false,
+    }
+}
+
+fn is_empty_dir(path: &Path) -> bool {
+    std::fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none())
+}
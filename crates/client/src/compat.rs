@@ -0,0 +1,140 @@
+//! Applying a game's [compatibility settings](Compat) on Windows.
+//!
+//! They're set as its executable's layers under `HKEY_CURRENT_USER`, like Explorer's
+//! Compatibility tab does. They're only for the current user, so setting them doesn't need an
+//! administrator.
+//!
+//! Settings taken out of the game list are removed the next time the game is run.
+
+use std::{io, path::Path};
+
+use common::{Compat, DpiScaling};
+
+/// Where the layers are, under `HKEY_CURRENT_USER`. Each value is named after an executable's
+/// full path.
+pub const LAYERS_KEY: &str = r"Software\Microsoft\Windows NT\CurrentVersion\AppCompatFlags\Layers";
+
+/// The layers that apply `compat`, written like Explorer writes them, e.g.
+/// `~ DISABLEDXMAXIMIZEDWINDOWEDMODE HIGHDPIAWARE`. `None` if there aren't any.
+pub fn layers(compat: &Compat) -> Option<String> {
+    let mut layers = Vec::new();
+    if compat.disable_fullscreen_optimizations {
+        layers.push("DISABLEDXMAXIMIZEDWINDOWEDMODE");
+    }
+    match compat.dpi_scaling {
+        Some(DpiScaling::Application) => layers.push("HIGHDPIAWARE"),
+        Some(DpiScaling::System) => layers.push("DPIUNAWARE"),
+        Some(DpiScaling::SystemEnhanced) => layers.extend(["GDIDPISCALING", "DPIUNAWARE"]),
+        None => {}
+    }
+    (!layers.is_empty()).then(|| format!("~ {}", layers.join(" ")))
+}
+
+/// Sets the layers for `exe` from `compat`, or [clears](clear) them if it doesn't have any, in
+/// case it used to. Does nothing if this isn't Windows.
+///
+/// # Errors
+/// Returns an error if the registry can't be written to.
+pub fn apply(exe: &Path, compat: &Compat) -> io::Result<()> {
+    let Some(layers) = layers(compat) else {
+        return clear(exe);
+    };
+    #[cfg(windows)]
+    {
+        registry::set(exe, &layers)
+    }
+    #[cfg(not(windows))]
+    {
+        tracing::debug!("not applying {layers} to {exe:?}: they're only for Windows");
+        Ok(())
+    }
+}
+
+/// Removes the layers for `exe`, like when its game is deleted. It's fine if it doesn't have any.
+///
+/// # Errors
+/// Returns an error if the registry can't be written to.
+// it can only be const where there's no registry
+#[allow(clippy::missing_const_for_fn)]
+pub fn clear(exe: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        registry::delete(exe)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = exe;
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod registry {
+    use std::{ffi::OsStr, io, os::windows::ffi::OsStrExt, path::Path};
+
+    use windows_sys::Win32::{
+        Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS},
+        System::Registry::{
+            RegCloseKey, RegCreateKeyExW, RegDeleteKeyValueW, RegSetValueExW, HKEY,
+            HKEY_CURRENT_USER, KEY_SET_VALUE, REG_OPTION_NON_VOLATILE, REG_SZ,
+        },
+    };
+
+    use super::LAYERS_KEY;
+
+    /// `s` as a null-terminated UTF-16 string.
+    fn wide(s: &OsStr) -> Vec<u16> {
+        s.encode_wide().chain(std::iter::once(0)).collect()
+    }
+
+    fn result(code: u32) -> io::Result<()> {
+        if code == ERROR_SUCCESS {
+            Ok(())
+        } else {
+            Err(io::Error::from_raw_os_error(
+                i32::try_from(code).unwrap_or(i32::MAX),
+            ))
+        }
+    }
+
+    pub fn set(exe: &Path, layers: &str) -> io::Result<()> {
+        let key = wide(OsStr::new(LAYERS_KEY));
+        let name = wide(exe.as_os_str());
+        let data = wide(OsStr::new(layers));
+        let size = u32::try_from(data.len() * 2).unwrap_or(u32::MAX);
+        let mut opened: HKEY = 0;
+        // SAFETY: `key` is null-terminated, and the default security attributes are used. The key
+        // is created for users that haven't had any layers set yet
+        result(unsafe {
+            RegCreateKeyExW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                0,
+                std::ptr::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_SET_VALUE,
+                std::ptr::null(),
+                &mut opened,
+                std::ptr::null_mut(),
+            )
+        })?;
+        // SAFETY: `name` is null-terminated, and `data` is valid for `size` bytes
+        let set = result(unsafe {
+            RegSetValueExW(opened, name.as_ptr(), 0, REG_SZ, data.as_ptr().cast(), size)
+        });
+        // SAFETY: the key was opened above, and isn't used after this
+        unsafe { RegCloseKey(opened) };
+        set
+    }
+
+    pub fn delete(exe: &Path) -> io::Result<()> {
+        let key = wide(OsStr::new(LAYERS_KEY));
+        let name = wide(exe.as_os_str());
+        // SAFETY: the strings are null-terminated
+        let code = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr()) };
+        if code == ERROR_FILE_NOT_FOUND {
+            return Ok(());
+        }
+        result(code)
+    }
+}
@@ -1,126 +1,701 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs::File;
-use std::io::{Cursor, Write};
-use std::path::Path;
+use std::io::{Read, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::{ClientError, Result};
-use crate::{Ctx, Game};
+use crate::formats::{self, ArchiveFile, Extractor};
+use crate::manifest::{Manifest, ManifestEntry};
+use crate::sources::DownloadSource;
+use crate::{check_space, is_disk_full, ClientError, Result};
+use crate::{Ctx, Game, GameStatus, PartialDownload, Work};
+use common::{GameId, GameInfo, GameSource};
 use futures::StreamExt;
-use tl::ParserOptions;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_RANGE, ETAG, LAST_MODIFIED};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::watch;
 
+/// How many bytes to download between saving the download's progress to the config.
+const CHECKPOINT_INTERVAL: u64 = 16 * 1024 * 1024;
+
+/// The size of a 7zip file's signature header. Anything smaller can't be an archive.
+pub const MIN_ARCHIVE_SIZE: u64 = 32;
+
+/// How long [`estimate_downloads`] waits for a game's size before counting it as unknown.
+pub const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many bytes from the start of a file [`detect_archive_file`] looks at. HTML pages can start
+/// with whitespace, so this is more than any archive's signature.
+const DETECT_LEN: u64 = 512;
+
+/// Passwords commonly used for game archives, tried in order when neither
+/// [`GameInfo::zip_password`] nor [`Config::default_zip_password`](crate::Config::default_zip_password)
+/// opens a game's archive.
+pub const KNOWN_PASSWORDS: &[&str] = &["game", "games", "password"];
+
+/// What kind of file an archive is, going by its first bytes.
+///
+/// Tar files are extracted as they download when a game is installed for the first time, since
+/// they can be read front to back. Every other kind is downloaded to a file and extracted from
+/// it, and so are tar files when the file is needed anyway: to resume a download, [keep the
+/// archive](crate::Config::keep_archives) or update a game that's already installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// The kind game archives usually are. Its header is at the end of the file, so it can't be
+    /// extracted while it's downloading.
+    SevenZip,
+    /// Its list of files is at the end of the file, so it can't be extracted while it's
+    /// downloading either.
+    Zip,
+    /// A gzipped tar file, extracted as it downloads. When it's extracted from a file instead,
+    /// it's read once before that to list its files.
+    TarGz,
+    /// An xz compressed tar file, extracted like [`Self::TarGz`].
+    TarXz,
+    /// A web page, like a host's error page, downloaded instead of the archive.
+    Html,
+    Unknown,
+}
+
+impl fmt::Display for ArchiveKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::SevenZip => "7z",
+            Self::Zip => "zip",
+            Self::TarGz => "tar.gz",
+            Self::TarXz => "tar.xz",
+            Self::Html => "HTML",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+/// Works out what kind of archive a file is from its first bytes, returning
+/// [`ArchiveKind::Unknown`] if it isn't one that's recognized.
+pub fn detect_archive(header: &[u8]) -> ArchiveKind {
+    const SIGNATURES: &[(&[u8], ArchiveKind)] = &[
+        (b"7z\xBC\xAF\x27\x1C", ArchiveKind::SevenZip),
+        (b"PK\x03\x04", ArchiveKind::Zip),
+        // an empty zip file
+        (b"PK\x05\x06", ArchiveKind::Zip),
+        (b"\x1F\x8B", ArchiveKind::TarGz),
+        (b"\xFD7zXZ\x00", ArchiveKind::TarXz),
+    ];
+    if let Some((_, kind)) = SIGNATURES.iter().find(|(sig, _)| header.starts_with(sig)) {
+        return *kind;
+    }
+
+    let text = header.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(header);
+    let text = text.trim_ascii_start();
+    let starts_with = |prefix: &[u8]| {
+        text.get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    };
+    if starts_with(b"<!doctype html") || starts_with(b"<html") {
+        ArchiveKind::Html
+    } else {
+        ArchiveKind::Unknown
+    }
+}
+
+/// Works out what kind of archive the file at `path` is, see [`detect_archive`].
+///
+/// # Errors
+/// Returns an error if the file can't be read.
+pub fn detect_archive_file(path: &Path) -> std::io::Result<ArchiveKind> {
+    let mut header = Vec::new();
+    File::open(path)?
+        .take(DETECT_LEN)
+        .read_to_end(&mut header)?;
+    Ok(detect_archive(&header))
+}
+
+/// Somewhere to report how far a download or extraction has got, as `(done, total)`.
+///
+/// Any `Fn(u64, u64)` closure works, so embedders can show progress however they like. The
+/// client uses a [`watch::Sender`], whose receiver is kept in the game's [`GameStatus`].
+pub trait Progress: Send + Sync {
+    /// Reports that `done` of `total` bytes (or files, for extraction) are done. A `total` of 0
+    /// means it isn't known.
+    fn report(&self, done: u64, total: u64);
+}
+
+impl<F: Fn(u64, u64) + Send + Sync> Progress for F {
+    fn report(&self, done: u64, total: u64) {
+        self(done, total);
+    }
+}
+
+impl Progress for watch::Sender<(u64, u64)> {
+    fn report(&self, done: u64, total: u64) {
+        if self.send((done, total)).is_err() {
+            tracing::warn!("progress receiver dropped");
+        }
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
-/// Downloads a game from Google Drive.
-/// Returns the zip file as a byte array (the password is "game").
+/// Downloads a game from `source`, resuming from [`Game::partial`] if the archive hasn't
+/// changed since.
+/// Returns the path of the downloaded 7zip file.
 ///
 /// # Errors
 /// Returns an error if the game is not found, the download fails,
-/// or Google Drive's virus scanning feature breaks things.
+/// or the source can't find the archive.
+/// Returns [`ClientError::DownloadIncomplete`] if fewer bytes arrive than the server said it
+/// would send; what did arrive is kept so the download can be resumed.
+/// Returns [`ClientError::EmptyArchive`] if the download is too small to be an archive, or
+/// [`ClientError::WebPage`] if it's a web page.
+/// Returns [`ClientError::NotEnoughSpace`] if the server says the archive won't fit in the
+/// [staging directory](crate::Config::staging_dir).
 pub async fn download_game(
     game: Game,
     ctx: Ctx,
-    progress: watch::Sender<(u64, u64)>,
-) -> Result<Vec<u8>> {
-    let gdrive_url = format!(
-        "https://drive.google.com/uc?export=download&id={}",
-        game.info.gdrive_id
-    );
+    source: &impl DownloadSource,
+    progress: &impl Progress,
+) -> Result<PathBuf> {
+    match download(game, ctx, source, progress, false).await? {
+        Download::Archive(path) => Ok(path),
+        Download::Extracted(_) => unreachable!("nothing is extracted as it downloads"),
+    }
+}
+
+/// What [`download`] did with a game's archive.
+enum Download {
+    /// It was downloaded to this file.
+    Archive(PathBuf),
+    /// It was [extracted as it downloaded](extract_as_it_downloads), after downloading this many
+    /// bytes.
+    Extracted(u64),
+}
+
+/// [`download_game`], extracting the archive as it downloads instead if `extract` is set and it's
+/// a tar file that isn't being resumed.
+#[allow(clippy::too_many_lines)]
+async fn download(
+    game: Game,
+    ctx: Ctx,
+    source: &impl DownloadSource,
+    progress: &impl Progress,
+    extract: bool,
+) -> Result<Download> {
+    let id = game.info.id;
+    let archive_path = ctx.config.archive_path(id);
+    let part_path = archive_path.with_extension("7z.part");
+
+    // only resume if we can make sure the archive hasn't changed since
+    let partial = game.partial.filter(|p| {
+        p.path == part_path
+            && p.validator().is_some()
+            && p.content_length.is_some()
+            && std::fs::metadata(&p.path).is_ok_and(|m| m.len() >= p.offset)
+    });
 
     // TODO: multithreaded download
-    let mut response = ctx.client.get(&gdrive_url).send().await?;
-
-    if response
-        .headers()
-        .get("Content-Type")
-        .is_some_and(|h| h.as_ref().starts_with(b"text/html"))
-    {
-        let real_url = {
-            let text = response.text().await?;
-            let dom = tl::parse(&text, ParserOptions::default())?;
-            let parser = dom.parser();
-            dom.get_element_by_id("download-form")
-                .ok_or(ClientError::BadDrive)?
-                .get(parser)
-                .ok_or(ClientError::BadDrive)?
-                .as_tag()
-                .ok_or(ClientError::BadDrive)?
-                .attributes()
-                .get("action")
-                .ok_or(ClientError::BadDrive)?
-                .ok_or(ClientError::BadDrive)?
-                .as_utf8_str()
-                .replace("&amp;", "&")
-        };
+    let mut response = source
+        .fetch(&ctx, partial.as_ref())
+        .await?
+        .error_for_status()?;
+
+    let resumed = partial.as_ref().filter(|p| {
+        response.status() == StatusCode::PARTIAL_CONTENT
+            && content_range_total(response.headers()) == p.content_length
+    });
+    // a server that ignores `If-Range` can send the rest of an archive that's changed, which
+    // isn't the start of the new one
+    if resumed.is_none() && response.status() == StatusCode::PARTIAL_CONTENT {
+        tracing::info!(
+            "got part of a different archive for {}; downloading all of it",
+            game.info.name
+        );
+        drop(response);
+        response = source.fetch(&ctx, None).await?.error_for_status()?;
+    }
 
-        tracing::info!("real google drive download URL: {real_url}");
+    // what's left to download when resuming, or the whole archive, which is `None` for chunked
+    // responses
+    let content_length = response.content_length();
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|h: &HeaderValue| h.to_str().ok())
+            .map(ToOwned::to_owned)
+    };
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    let mut stream = response.bytes_stream();
 
-        response = ctx.client.get(&real_url).send().await?;
+    // enough of a new download to tell whether it can be extracted as it downloads
+    let mut head = Vec::new();
+    if extract && resumed.is_none() {
+        while (head.len() as u64) < DETECT_LEN {
+            match stream.next().await {
+                Some(chunk) => head.extend_from_slice(&chunk?),
+                None => break,
+            }
+        }
+        let tar = match detect_archive(&head) {
+            ArchiveKind::TarGz => Some(formats::Tar::Gz),
+            ArchiveKind::TarXz => Some(formats::Tar::Xz),
+            _ => None,
+        };
+        if let Some(tar) = tar {
+            ctx.resumed_from.remove(&id);
+            // what was downloaded before is of an archive that's since changed
+            if let Some(mut game) = ctx.config.games().get_mut(&id) {
+                game.partial = None;
+            }
+            let stream = futures::stream::iter([Ok(head.into())]).chain(stream);
+            return extract_as_it_downloads(
+                &ctx,
+                &game.info,
+                tar,
+                stream,
+                content_length,
+                progress,
+            )
+            .await
+            .map(Download::Extracted);
+        }
     }
 
-    let content_length = response.content_length().ok_or(ClientError::BadDrive)?;
-    if progress.send((0, content_length)).is_err() {
-        tracing::warn!("progress receiver dropped");
+    if let Some(needed) = content_length {
+        let staging_dir = ctx.config.staging_dir();
+        tokio::fs::create_dir_all(&staging_dir).await?;
+        check_space(&staging_dir, needed)?;
+    }
+
+    let (mut file, mut partial) = if let Some(partial) = resumed {
+        tracing::info!(
+            "resuming download of {} at {} bytes",
+            game.info.name,
+            partial.offset
+        );
+        // a retry resuming further along is still the same download
+        ctx.resumed_from.entry(id).or_insert(partial.offset);
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&partial.path)
+            .await?;
+        // anything past the offset wasn't confirmed to be written
+        file.set_len(partial.offset).await?;
+        let mut file = tokio::io::BufWriter::new(file);
+        file.seek(SeekFrom::End(0)).await?;
+        (file, partial.clone())
+    } else {
+        if partial.is_some() {
+            tracing::info!(
+                "archive for {} changed; restarting download",
+                game.info.name
+            );
+        }
+        ctx.resumed_from.remove(&id);
+        let partial = PartialDownload {
+            path: part_path,
+            offset: 0,
+            content_length,
+            etag,
+            last_modified,
+        };
+        tokio::fs::create_dir_all(ctx.config.staging_dir()).await?;
+        let file = tokio::io::BufWriter::new(tokio::fs::File::create(&partial.path).await?);
+        (file, partial)
     };
 
-    let mut bytes = Vec::new();
-    let mut stream = response.bytes_stream();
-    let mut recvd = 0;
+    let content_length = partial.content_length;
+    // a total of 0 means the size isn't known
+    let total = content_length.unwrap_or(0);
+    progress.report(partial.offset, total);
+
+    let games = ctx.config.games();
+    let checkpoint = |partial: &PartialDownload| {
+        if let Some(mut game) = games.get_mut(&id) {
+            game.partial = Some(partial.clone());
+        }
+        ctx.config.save();
+    };
+    checkpoint(&partial);
+
+    // the start of the archive, if it was read to see what kind it is
+    let head = (!head.is_empty()).then(|| Ok(head.into()));
+    let mut stream = futures::stream::iter(head).chain(stream);
+    let mut recvd = partial.offset;
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
+        if ctx.pause.is_paused() {
+            tracing::info!("download of {} paused", game.info.name);
+            ctx.pause.wait().await;
+            tracing::info!("download of {} resumed", game.info.name);
+        }
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            // reported by the length check below, which says how much is missing
+            Err(e) if content_length.is_some() => {
+                tracing::warn!("download of {} was cut off: {e:#}", game.info.name);
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
         recvd += chunk.len() as u64;
+        ctx.stats.downloaded(chunk.len() as u64);
 
         tracing::trace!("received {} bytes", chunk.len());
 
-        if progress.send((recvd, content_length)).is_err() {
-            tracing::warn!("progress receiver dropped");
-        };
-        bytes.extend_from_slice(&chunk);
+        progress.report(recvd, total);
+        file.write_all(&chunk).await?;
+
+        if recvd - partial.offset >= CHECKPOINT_INTERVAL {
+            file.flush().await?;
+            file.get_ref().sync_data().await?;
+            partial.offset = recvd;
+            checkpoint(&partial);
+            // it's resumed from here once the window opens again
+            if !ctx.config.download_window_open() {
+                tracing::info!(
+                    "the download window closed; stopping the download of {}",
+                    game.info.name
+                );
+                return Err(ClientError::OutsideDownloadWindow);
+            }
+        }
+    }
+
+    file.flush().await?;
+    file.get_ref().sync_all().await?;
+    drop(file);
+
+    if let Some(expected) = content_length.filter(|&expected| expected != recvd) {
+        if recvd < expected {
+            partial.offset = recvd;
+            checkpoint(&partial);
+        }
+        return Err(ClientError::DownloadIncomplete {
+            got: recvd,
+            expected,
+        });
     }
 
-    Ok(bytes)
+    let kind = detect_archive_file(&partial.path)?;
+    if recvd < MIN_ARCHIVE_SIZE || kind == ArchiveKind::Html {
+        // there's nothing worth resuming
+        tokio::fs::remove_file(&partial.path).await?;
+        if let Some(mut game) = games.get_mut(&id) {
+            game.partial = None;
+        }
+        return Err(if kind == ArchiveKind::Html {
+            ClientError::WebPage
+        } else {
+            ClientError::EmptyArchive(recvd)
+        });
+    }
+
+    tokio::fs::rename(&partial.path, &archive_path).await?;
+    if let Some(mut game) = games.get_mut(&id) {
+        game.partial = None;
+    }
+    ctx.config.record_downloaded_archive(&game.info)?;
+
+    Ok(Download::Archive(archive_path))
 }
 
-/// Extracts a 7zip file to a directory.
+/// Downloads a game from each of its [sources](GameInfo::sources) in turn until one works,
+/// returning what was done with the archive and the source it came from. It's extracted as it
+/// downloads if `extract` is set and it can be, see [`download`].
 ///
 /// # Errors
-/// Returns an error if the 7zip file is invalid or the directory can't be written to.
+/// Returns the last source's error if none of them work, or the first error that another source
+/// wouldn't fix (like the disk being full).
+async fn download_from_any_source(
+    ctx: &Ctx,
+    mut game: Game,
+    progress: &impl Progress,
+    extract: bool,
+) -> Result<(Download, GameSource)> {
+    let mut sources = game.info.sources().into_iter().peekable();
+    while let Some(source) = sources.next() {
+        match download_with_retries(ctx, game.clone(), &source, progress, extract).await {
+            Ok(downloaded) => return Ok((downloaded, source)),
+            Err(e) if e.is_source_error() && sources.peek().is_some() => {
+                tracing::warn!(
+                    "downloading {} from {source} failed, trying the next source: {e}",
+                    game.info.name
+                );
+                if let Some(saved) = ctx.config.games().get(&game.info.id) {
+                    game.partial = saved.partial.clone();
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(ClientError::NoSources)
+}
+
+/// Runs [`download`], retrying up to [`Config::download_retries`] times if it fails in a way
+/// that might not happen again. Each retry resumes from where the last attempt got to, or starts
+/// over if it was being extracted as it downloaded.
+///
+/// [`Config::download_retries`]: crate::Config::download_retries
+async fn download_with_retries(
+    ctx: &Ctx,
+    mut game: Game,
+    source: &GameSource,
+    progress: &impl Progress,
+    extract: bool,
+) -> Result<Download> {
+    let mut retries = ctx.config.download_retries();
+    loop {
+        match download(game.clone(), ctx.clone(), source, progress, extract).await {
+            Err(e) if e.is_transient() && retries > 0 => {
+                retries -= 1;
+                tracing::warn!(
+                    "download of {} failed, retrying ({retries} retries left): {e}",
+                    game.info.name
+                );
+                if let Some(saved) = ctx.config.games().get(&game.info.id) {
+                    game.partial = saved.partial.clone();
+                }
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Gets the total length from a `Content-Range` header, e.g. `bytes 200-1000/1001`.
+fn content_range_total(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit_once('/')?
+        .1
+        .parse()
+        .ok()
+}
+
+/// The passwords to try for a game's archive: its own, then `default` (the config's default
+/// password, if it has one), then [`KNOWN_PASSWORDS`].
+#[must_use]
+pub fn password_candidates<'a>(info: &'a GameInfo, default: Option<&'a str>) -> Vec<&'a str> {
+    let mut candidates = info.zip_password.as_deref().into_iter().collect::<Vec<_>>();
+    if let Some(default) = default.filter(|default| !candidates.contains(default)) {
+        candidates.push(default);
+    }
+    for known in KNOWN_PASSWORDS {
+        if !candidates.contains(known) {
+            candidates.push(known);
+        }
+    }
+    candidates
+}
+
+/// Finds the first password in `candidates` that opens an archive, by decoding its first file.
+/// Kinds of archive that can't have passwords, like tar files, are opened by any of them, so the
+/// first is returned without reading the archive.
+///
+/// # Errors
+/// Returns [`ClientError::BadZipPassword`] if none of them work, [`ClientError::EmptyArchive`]
+/// if the file is too small to be an archive, [`ClientError::UnsupportedArchive`] or
+/// [`ClientError::WebPage`] if it isn't a kind that can be [extracted](formats::extractor), or
+/// another error if the archive can't be read at all.
+pub fn find_password<'a>(
+    archive: &Path,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Result<&'a str> {
+    if !archive.is_file() {
+        return Err(ClientError::ArchiveNotAFile(archive.to_path_buf()));
+    }
+    let size = std::fs::metadata(archive)?.len();
+    if size < MIN_ARCHIVE_SIZE {
+        return Err(ClientError::EmptyArchive(size));
+    }
+    let extractor = archive_extractor(archive)?;
+    if !extractor.has_passwords() {
+        return Ok(candidates.into_iter().next().unwrap_or_default());
+    }
+    for password in candidates {
+        let res = extractor.for_each_file(archive, password, &mut |_, reader| {
+            // a wrong password gives garbage, which fails to decompress or its CRC check
+            std::io::copy(reader, &mut std::io::sink())?;
+            Ok(false)
+        });
+        match res {
+            Ok(()) => return Ok(password),
+            Err(e) => tracing::debug!("password {password:?} didn't work: {e}"),
+        }
+    }
+    Err(ClientError::BadZipPassword)
+}
+
+/// What reads the archive at `path`, going by its first bytes.
+///
+/// # Errors
+/// Returns [`ClientError::WebPage`] if it's a web page, [`ClientError::UnsupportedArchive`] if
+/// it's another kind that can't be extracted, or an error if it can't be read.
+fn archive_extractor(path: &Path) -> Result<&'static dyn Extractor> {
+    match detect_archive_file(path)? {
+        ArchiveKind::Html => Err(ClientError::WebPage),
+        kind => formats::extractor(kind).ok_or(ClientError::UnsupportedArchive(kind)),
+    }
+}
+
+/// Extracts an archive to a directory, recording the extracted files in its [`Manifest`].
 ///
-/// # Panics
-/// Panics if a the 7zip file doesn't have a single root directory.
-#[allow(clippy::needless_pass_by_value, clippy::cognitive_complexity)]
+/// It can be any kind with an [extractor](formats::extractor), the same as [`find_password`]
+/// takes. Tar files don't list their files up front, so they're read twice: once to count them.
+///
+/// `strip_prefix` leading directories are removed from the path of each file (see
+/// [`GameInfo::strip_prefix`]). If it's `None`, the archive's top-level directory is removed if
+/// every file is in it.
+///
+/// If `incremental` is set and the directory already has a manifest, files whose CRC hasn't
+/// changed are left alone and files that are no longer in the archive are deleted. Files that
+/// aren't in the manifest (like saves) are never touched.
+///
+/// If `hash_files` is set, the SHA-256 of each file is recorded in the manifest as it's written.
+/// Files left alone keep the hash they had.
+///
+/// # Errors
+/// Returns [`ClientError::DiskFull`] if the disk fills up, [`ClientError::ArchiveNotAFile`] if
+/// the archive is missing, [`ClientError::Canceled`] if `cancel` is set before it finishes, or
+/// another error if it's invalid or the directory can't be written to.
+#[allow(clippy::too_many_arguments, clippy::fn_params_excessive_bools)]
 pub fn extract_zip_with_password(
-    bytes: &[u8],
+    archive: &Path,
     dest: &Path,
     password: &str,
-    progress: watch::Sender<(u64, u64)>,
+    strip_prefix: Option<u32>,
+    incremental: bool,
+    hash_files: bool,
+    progress: impl Progress,
+    cancel: &AtomicBool,
+) -> Result<()> {
+    if !archive.is_file() {
+        return Err(ClientError::ArchiveNotAFile(archive.to_path_buf()));
+    }
+    let extractor = archive_extractor(archive)?;
+    let names = extractor.file_names(archive, password)?;
+    let strip = strip_prefix.map_or_else(
+        || common_root_depth(names.iter().map(String::as_str)),
+        |n| n as usize,
+    );
+    extract_files(
+        dest,
+        strip,
+        incremental,
+        hash_files,
+        progress,
+        names.len() as u64,
+        cancel,
+        |f| extractor.for_each_file(archive, password, f),
+    )
+}
+
+/// Extracts the files `for_each_file` hands over to `dest`, for [`extract_zip_with_password`]
+/// and [`extract_as_it_downloads`]. `total_files` is only for the progress, and is 0 if it isn't
+/// known.
+#[allow(
+    clippy::needless_pass_by_value,
+    clippy::cognitive_complexity,
+    clippy::too_many_lines,
+    clippy::too_many_arguments
+)]
+fn extract_files(
+    dest: &Path,
+    strip: usize,
+    incremental: bool,
+    hash_files: bool,
+    progress: impl Progress,
+    total_files: u64,
+    cancel: &AtomicBool,
+    for_each_file: impl FnOnce(
+        &mut dyn FnMut(ArchiveFile, &mut dyn Read) -> std::io::Result<bool>,
+    ) -> Result<()>,
 ) -> Result<()> {
-    let mut sz =
-        sevenz_rust::SevenZReader::new(Cursor::new(bytes), bytes.len() as u64, password.into())?;
-    let total_files = sz.archive().files.len();
     let mut files = 0;
-    sz.for_each_entries(|entry, reader| {
-        if entry.is_directory() {
-            return Ok(true); // we create the directory before creating files; removing this will cause an error with `File::create`
+
+    let previous = if incremental {
+        Manifest::load(dest)
+    } else {
+        None
+    };
+    if incremental && previous.is_none() {
+        tracing::info!("no manifest in {dest:?}; extracting everything");
+    }
+    // only the files changed, so the game's setup is as up to date as it was
+    let mut manifest = Manifest {
+        post_install_hooks: previous.as_ref().and_then(|m| m.post_install_hooks),
+        ..Default::default()
+    };
+    // errors inside the loop can be turned into the archive's own errors, so remember if the
+    // disk filled up
+    let mut disk_full = false;
+    let mut note_disk_full = |e: std::io::Error| {
+        disk_full |= is_disk_full(&e);
+        e
+    };
+
+    let mut canceled = false;
+
+    let res = for_each_file(&mut |entry, reader| {
+        if cancel.load(Ordering::Relaxed) {
+            canceled = true;
+            return Ok(false);
         }
 
-        let path = Path::new(entry.name()); // TODO: handle invalid paths; we don't really need to worry about this but it's a good habit
-        let mut components = path.components();
-        components.next();
-        let path = components.as_path();
-        let path = dest.join(path);
+        let Some(key) = entry_key(entry.name, strip) else {
+            tracing::warn!("{:?} isn't a safe path to extract to; skipping", entry.name);
+            std::io::copy(reader, &mut std::io::sink())?;
+            files += 1;
+            return Ok(true);
+        };
+        let path = dest.join(&key);
+
+        let mut manifest_entry = ManifestEntry {
+            size: entry.size,
+            crc: entry.crc,
+            sha256: None,
+        };
 
+        if let Some(previous) = previous
+            .as_ref()
+            .filter(|p| p.is_unchanged(dest, &key, manifest_entry))
+        {
+            tracing::trace!("{key} is unchanged; skipping");
+            manifest_entry.sha256 = previous.files[&key].sha256;
+            manifest.files.insert(key, manifest_entry);
+            // the rest of the archive comes after this entry in the stream
+            std::io::copy(reader, &mut std::io::sink())?;
+            files += 1;
+            progress.report(files, total_files);
+            return Ok(true);
+        }
+
+        let mut hasher = hash_files.then(Sha256::new);
         let mut buf = [0u8; 1024];
-        std::fs::create_dir_all(path.parent().unwrap())?;
-        let mut file = File::create(path)?;
+        std::fs::create_dir_all(path.parent().unwrap()).map_err(&mut note_disk_full)?;
+        let mut file = File::create(path).map_err(&mut note_disk_full)?;
         let res = loop {
+            if cancel.load(Ordering::Relaxed) {
+                canceled = true;
+                break Ok(false);
+            }
             let read_size = reader.read(&mut buf)?;
             if read_size == 0 {
                 break Ok(true);
             }
-            file.write_all(&buf[..read_size])?;
-            if progress.send((files, total_files as u64)).is_err() {
-                tracing::warn!("progress receiver dropped");
-            };
+            file.write_all(&buf[..read_size])
+                .map_err(&mut note_disk_full)?;
+            if let Some(hasher) = &mut hasher {
+                hasher.update(&buf[..read_size]);
+            }
+            progress.report(files, total_files);
         };
+        manifest_entry.sha256 = hasher.map(|h| h.finalize().into());
+        manifest.files.insert(key, manifest_entry);
 
         #[cfg(unix)]
         {
@@ -132,6 +707,747 @@ pub fn extract_zip_with_password(
 
         files += 1;
         res
-    })?;
+    });
+    if disk_full {
+        return Err(ClientError::DiskFull);
+    }
+    res?;
+    if canceled {
+        return Err(ClientError::Canceled);
+    }
+
+    if let Some(previous) = previous {
+        for key in previous.files.keys() {
+            if manifest.files.contains_key(key) {
+                continue;
+            }
+            tracing::debug!("removing {key}; it's no longer in the archive");
+            match std::fs::remove_file(dest.join(key)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+    }
+
+    std::fs::create_dir_all(dest)?;
+    manifest.save(dest)?;
+    Ok(())
+}
+
+/// The directories and file name in the name of a file in an archive. Both `/` and `\` separate
+/// them, since archives made on Windows can use either.
+fn entry_components(name: &str) -> impl Iterator<Item = &str> {
+    name.split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+}
+
+/// Turns the name of a file in an archive into the path it's extracted to, relative to the game
+/// directory and with `/` separators, after removing `strip` leading directories.
+///
+/// Characters Windows doesn't allow in file names are replaced with `_`, and so are names Windows
+/// reserves for devices (like `CON`). Returns `None` for names that would leave the game
+/// directory (like `../../file`) or are stripped away entirely.
+fn entry_key(name: &str, strip: usize) -> Option<String> {
+    const RESERVED: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+
+    let mut key = Vec::new();
+    for component in entry_components(name) {
+        if component == ".." {
+            return None;
+        }
+        let mut component = component
+            .chars()
+            .map(|c| {
+                if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') {
+                    '_'
+                } else {
+                    c
+                }
+            })
+            .collect::<String>()
+            // Windows drops these, so `a.` and `a` would be the same file
+            .trim_end_matches(['.', ' '])
+            .to_owned();
+        let stem = component
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let device = RESERVED.contains(&stem.as_str())
+            || (stem.len() == 4
+                && (stem.starts_with("COM") || stem.starts_with("LPT"))
+                && stem.ends_with(|c: char| c.is_ascii_digit()));
+        if component.is_empty() || device {
+            component.insert(0, '_');
+        }
+        key.push(component);
+    }
+    let key = key.get(strip..)?.join("/");
+    (!key.is_empty()).then_some(key)
+}
+
+/// How many leading directories every file in an archive has in common: 1 if they're all in the
+/// same top-level directory, 0 otherwise.
+fn common_root_depth<'a>(names: impl IntoIterator<Item = &'a str>) -> usize {
+    let mut root = None;
+    for name in names {
+        let mut components = entry_components(name);
+        let (Some(first), Some(_)) = (components.next(), components.next()) else {
+            // a file at the root of the archive
+            return 0;
+        };
+        match root {
+            None => root = Some(first),
+            Some(root) if root != first => return 0,
+            Some(_) => {}
+        }
+    }
+    usize::from(root.is_some())
+}
+
+/// How many chunks of a download [`extract_as_it_downloads`] lets get ahead of the extraction.
+const STREAM_CHUNKS: usize = 64;
+
+/// Where [`extract_as_it_downloads`] extracts a game to before it's moved into its directory.
+fn streaming_dir(game_dir: &Path) -> PathBuf {
+    game_dir.with_extension("extracting")
+}
+
+/// Extracts a tar file from the download's `stream` as it arrives, without writing the archive to
+/// disk, returning how many bytes were downloaded.
+///
+/// The files are extracted next to the game's directory and moved into it once all of them are
+/// there, since whether they're all in one top-level directory isn't known until then. There's
+/// nothing to resume if it fails, so retrying it starts over.
+///
+/// # Errors
+/// Returns [`ClientError::Canceled`] if the install is canceled with [`Ctx::cancel_install`],
+/// [`ClientError::DownloadIncomplete`] if fewer bytes arrive than the server said it would send,
+/// or another error if downloading or extracting fails.
+async fn extract_as_it_downloads<S, B>(
+    ctx: &Ctx,
+    info: &GameInfo,
+    tar: formats::Tar,
+    mut stream: S,
+    content_length: Option<u64>,
+    progress: &impl Progress,
+) -> Result<u64>
+where
+    S: futures::Stream<Item = reqwest::Result<B>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    let dest = ctx.config.game_dir(info.id);
+    let staging = streaming_dir(&dest);
+    // left by an install that was interrupted
+    crate::cleanup::remove_dir_all_retrying(&staging).await?;
+    tokio::fs::create_dir_all(&staging).await?;
+    if let Some(needed) = content_length {
+        // the files take up at least as much space as the archive
+        check_space(&staging, needed)?;
+    }
+    tracing::info!("extracting {} as it downloads", info.name);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    ctx.installs.insert(info.id, cancel.clone());
+    let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHUNKS);
+    let extraction = tokio::task::spawn_blocking({
+        let staging = staging.clone();
+        let strip = info.strip_prefix.map_or(0, |n| n as usize);
+        let hash_files = ctx.config.hash_files();
+        move || {
+            let reader = ChannelReader {
+                chunks: rx,
+                chunk: std::io::Cursor::default(),
+            };
+            let progress = |_: u64, _: u64| {};
+            extract_files(
+                &staging,
+                strip,
+                false,
+                hash_files,
+                progress,
+                0,
+                &cancel,
+                |f| tar.for_each_file_in(reader, f),
+            )
+        }
+    });
+
+    let total = content_length.unwrap_or(0);
+    let mut recvd = 0;
+    progress.report(recvd, total);
+    let downloaded = async {
+        let mut next_check = CHECKPOINT_INTERVAL;
+        while let Some(chunk) = stream.next().await {
+            if ctx.pause.is_paused() {
+                tracing::info!("download of {} paused", info.name);
+                ctx.pause.wait().await;
+                tracing::info!("download of {} resumed", info.name);
+            }
+            let chunk = chunk?;
+            let chunk = chunk.as_ref();
+            recvd += chunk.len() as u64;
+            ctx.stats.downloaded(chunk.len() as u64);
+            progress.report(recvd, total);
+            // the extraction stopped early, and says why
+            if tx.send(Ok(chunk.to_vec())).await.is_err() {
+                return Ok(());
+            }
+            if recvd >= next_check {
+                next_check += CHECKPOINT_INTERVAL;
+                if !ctx.config.download_window_open() {
+                    tracing::info!(
+                        "the download window closed; stopping the download of {}",
+                        info.name
+                    );
+                    return Err(ClientError::OutsideDownloadWindow);
+                }
+            }
+        }
+        match content_length {
+            Some(expected) if expected != recvd => Err(ClientError::DownloadIncomplete {
+                got: recvd,
+                expected,
+            }),
+            _ => Ok(()),
+        }
+    }
+    .await;
+    // a download that stopped partway mustn't look like the end of the archive
+    if let Err(e) = &downloaded {
+        let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+    }
+    drop(tx);
+    let extracted = extraction.await.expect("extraction task panicked");
+    ctx.installs.remove(&info.id);
+
+    let res = downloaded
+        .and(extracted)
+        .and_then(|()| move_extracted(&staging, &dest, info.strip_prefix.is_none()));
+    if res.is_err() {
+        if let Err(e) = crate::cleanup::remove_dir_all_retrying(&staging).await {
+            tracing::warn!("failed to remove {staging:?}: {e:#}");
+        }
+    }
+    res.map(|()| recvd)
+}
+
+/// Reads the chunks of a download that [`extract_as_it_downloads`] sends it, on a blocking thread.
+struct ChannelReader {
+    chunks: tokio::sync::mpsc::Receiver<std::io::Result<Vec<u8>>>,
+    chunk: std::io::Cursor<Vec<u8>>,
+}
+
+impl Read for ChannelReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let read = self.chunk.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            match self.chunks.blocking_recv() {
+                Some(chunk) => self.chunk = std::io::Cursor::new(chunk?),
+                // the whole download has been read
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Moves the files [`extract_as_it_downloads`] extracted to `staging` into `dest`, the game's
+/// directory, leaving out the archive's top-level directory if `strip_root` is set and every file
+/// is in it.
+fn move_extracted(staging: &Path, dest: &Path, strip_root: bool) -> Result<()> {
+    let mut manifest = Manifest::load(staging).unwrap_or_default();
+    let strip_root =
+        strip_root && common_root_depth(manifest.files.keys().map(String::as_str)) == 1;
+    let root = manifest
+        .files
+        .keys()
+        .next()
+        .filter(|_| strip_root)
+        .and_then(|key| key.split_once('/'))
+        .map(|(root, _)| root.to_owned());
+    let Some(root) = root else {
+        std::fs::rename(staging, dest)?;
+        return Ok(());
+    };
+
+    std::fs::rename(staging.join(&root), dest)?;
+    let prefix = format!("{root}/");
+    manifest.files = std::mem::take(&mut manifest.files)
+        .into_iter()
+        .filter_map(|(key, entry)| Some((key.strip_prefix(&prefix)?.to_owned(), entry)))
+        .collect();
+    manifest.save(dest)?;
+    std::fs::remove_dir_all(staging)?;
+    Ok(())
+}
+
+/// What a finished [`install_game`] did, for callers that report on installs without watching
+/// the game's status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallOutcome {
+    pub game_id: GameId,
+    /// Where the game was extracted to.
+    pub install_dir: PathBuf,
+    /// How much of the archive was downloaded by this install. Zero if an archive that was
+    /// already on disk was installed, and only the rest of it if a download was resumed.
+    pub bytes_downloaded: u64,
+    /// How long downloading and extracting took.
+    pub duration: Duration,
+}
+
+/// Downloads and extracts a game whose status has already been set to
+/// [`GameStatus::Downloading`], leaving it [`GameStatus::Ready`].
+///
+/// Downloads that fail partway are retried up to [`Config::download_retries`] times, then the
+/// game's [mirrors](GameInfo::mirrors) are tried. If anything still fails, the game is marked
+/// [`GameStatus::Failed`] with the error. An interrupted download is kept so it can be resumed,
+/// and an archive that failed to extract (or whose extraction was canceled with
+/// [`Ctx::cancel_install`]) is kept so installing again only has to extract it.
+///
+/// A valid archive in the [cache](crate::Config::cached_archive) is installed instead of
+/// downloading it, and if [`Config::keep_archives`] is set, the downloaded archive is cached.
+/// Otherwise a game that isn't installed yet is extracted as it downloads if its archive is a
+/// tar file (see [`ArchiveKind`]), and stays [`GameStatus::Downloading`] until it's done.
+///
+/// Downloading and extracting each wait for their turn with [`Ctx::start_work`].
+///
+/// A successful install is also sent to [`Config::install_outcomes`] subscribers.
+///
+/// # Errors
+/// Returns an error if the download or extraction fails.
+///
+/// [`Config::download_retries`]: crate::Config::download_retries
+/// [`Config::keep_archives`]: crate::Config::keep_archives
+/// [`Config::install_outcomes`]: crate::Config::install_outcomes
+pub async fn install_game(
+    ctx: &Ctx,
+    game: Game,
+    progress: watch::Sender<(u64, u64)>,
+) -> Result<InstallOutcome> {
+    install(ctx, game, progress, false).await
+}
+
+/// Installs a game like [`install_game`], then runs its `post_install` hook (see
+/// [`run_post_install`](crate::py::run_post_install)) before it's [`GameStatus::Ready`], so it
+/// can't be run or deleted while the hook sets it up. A hook that fails is shown on the game,
+/// but the install still succeeds.
+///
+/// # Errors
+/// Returns an error if the download or extraction fails.
+pub async fn install_game_and_set_up(
+    ctx: &Ctx,
+    game: Game,
+    progress: watch::Sender<(u64, u64)>,
+) -> Result<InstallOutcome> {
+    let id = game.info.id;
+    let outcome = install(ctx, game, progress, true).await?;
+    let _ = crate::py::run_post_install(ctx, id, false, false).await;
+    if let Some(mut game) = ctx.config.games().get_mut(&id) {
+        ctx.config.set_status(&mut game, GameStatus::Ready);
+    }
+    Ok(outcome)
+}
+
+/// [`install_game`], leaving the game [`GameStatus::Installing`] instead of ready if `set_up`
+/// is set, for [`install_game_and_set_up`].
+#[allow(clippy::too_many_lines)]
+async fn install(
+    ctx: &Ctx,
+    game: Game,
+    progress: watch::Sender<(u64, u64)>,
+    set_up: bool,
+) -> Result<InstallOutcome> {
+    let games = ctx.config.games();
+    let id = game.info.id;
+    let started = Instant::now();
+    let mut bytes_downloaded = 0;
+
+    let res = async {
+        let archive = ctx.config.archive_path(id);
+        let downloaded = ctx.config.downloaded_archive(&game.info);
+        if downloaded.is_none() && archive.is_file() {
+            tracing::info!(
+                "removing the archive from a failed install of an old version of {}",
+                game.info.name
+            );
+            tokio::fs::remove_file(&archive).await?;
+            ctx.config.forget_downloaded_archive(id);
+        }
+        let cached = ctx.config.cached_archive(&game.info);
+        let archive = if let Some(archive) = downloaded {
+            tracing::info!(
+                "reusing the archive from a failed install of {}",
+                game.info.name
+            );
+            archive
+        } else if let Some(cached) = &cached {
+            tracing::info!("installing {} from the archive cache", game.info.name);
+            ctx.config.touch_cached_archive(id);
+            cached.clone()
+        } else {
+            ctx.resumed_from.remove(&id);
+            // updates are extracted from a file, and so are archives that are kept
+            let extract = !ctx.config.keep_archives() && !ctx.config.game_dir(id).exists();
+            let mut game = game.clone();
+            let downloaded = loop {
+                let Some(permit) = ctx.start_download(id).await else {
+                    tracing::info!("{} was taken out of the install queue", game.info.name);
+                    return Err(ClientError::Canceled);
+                };
+                match download_from_any_source(ctx, game.clone(), &progress, extract).await {
+                    Err(ClientError::OutsideDownloadWindow) => {
+                        drop(permit);
+                        // it goes first once the window opens, since it's already started
+                        ctx.config.enqueue_install(id);
+                        ctx.move_in_install_queue(id, 0);
+                        if let Some(saved) = games.get(&id) {
+                            game.partial = saved.partial.clone();
+                        }
+                    }
+                    res => break res,
+                }
+            };
+            let resumed_at = ctx.resumed_from.remove(&id).map_or(0, |(_, at)| at);
+            let (downloaded, source) = downloaded?;
+            tracing::info!("downloaded {} from {source}", game.info.name);
+            match downloaded {
+                Download::Archive(archive) => {
+                    bytes_downloaded = tokio::fs::metadata(&archive)
+                        .await?
+                        .len()
+                        .saturating_sub(resumed_at);
+                    archive
+                }
+                Download::Extracted(downloaded) => {
+                    return Ok(InstallOutcome {
+                        game_id: id,
+                        install_dir: ctx.config.game_dir(id),
+                        bytes_downloaded: downloaded,
+                        duration: started.elapsed(),
+                    });
+                }
+            }
+        };
+        tracing::info!("downloaded game: {game:?}; extracting...");
+
+        let (tx, rx) = watch::channel((0, 0));
+        if let Some(mut game) = games.get_mut(&id) {
+            ctx.config.set_status(&mut game, GameStatus::Installing(rx));
+        }
+        let dest = ctx.config.game_dir(id);
+        let fresh = !dest.exists();
+        let cancel = Arc::new(AtomicBool::new(false));
+        ctx.installs.insert(id, cancel.clone());
+        let permit = ctx.start_work(Work::Extraction).await;
+        let res = tokio::task::spawn_blocking({
+            let archive = archive.clone();
+            let dest = dest.clone();
+            let incremental = ctx.config.incremental_updates();
+            let hash_files = ctx.config.hash_files();
+            let info = game.info.clone();
+            let default_password = ctx.config.default_zip_password();
+            move || {
+                let candidates = password_candidates(&info, default_password.as_deref());
+                let password = find_password(&archive, candidates)?;
+                extract_zip_with_password(
+                    &archive,
+                    &dest,
+                    password,
+                    info.strip_prefix,
+                    incremental,
+                    hash_files,
+                    tx,
+                    &cancel,
+                )
+            }
+        })
+        .await
+        .expect("extraction task panicked");
+        drop(permit);
+        ctx.installs.remove(&id);
+
+        // a canceled update leaves the old files, which installing again fixes up
+        if matches!(res, Err(ClientError::Canceled)) && fresh {
+            tracing::info!("install of {} canceled; removing {dest:?}", game.info.name);
+            if let Err(e) = crate::cleanup::remove_dir_all_retrying(&dest).await {
+                tracing::warn!("failed to remove {dest:?}: {e:#}");
+            }
+        }
+
+        match &res {
+            // the cached archive stays in the cache
+            Ok(()) if cached.as_ref() == Some(&archive) => {}
+            Ok(()) if ctx.config.keep_archives() => {
+                ctx.config.forget_downloaded_archive(id);
+                if let Err(e) = ctx.config.cache_archive(&game.info, &archive) {
+                    tracing::warn!("failed to cache archive {archive:?}: {e:#}");
+                }
+            }
+            Ok(()) => {
+                ctx.config.forget_downloaded_archive(id);
+                if let Err(e) = tokio::fs::remove_file(&archive).await {
+                    tracing::warn!("failed to remove archive {archive:?}: {e:#}");
+                }
+            }
+            // it might be what's broken, so the next install downloads it again
+            Err(e) if cached.as_ref() == Some(&archive) && !matches!(e, ClientError::Canceled) => {
+                ctx.config.uncache_archive(id);
+            }
+            // there's nothing to retry with
+            Err(ClientError::EmptyArchive(_)) => {
+                ctx.config.forget_downloaded_archive(id);
+                if let Err(e) = tokio::fs::remove_file(&archive).await {
+                    tracing::warn!("failed to remove archive {archive:?}: {e:#}");
+                }
+            }
+            Err(_) => tracing::info!("keeping {archive:?} so the install can be retried"),
+        }
+        res.map(|()| InstallOutcome {
+            game_id: id,
+            install_dir: dest,
+            bytes_downloaded,
+            duration: started.elapsed(),
+        })
+    }
+    .await;
+
+    if !matches!(res, Err(ClientError::Canceled)) {
+        ctx.stats.install_finished(res.is_ok());
+    }
+    if let Some(mut game) = games.get_mut(&id) {
+        let status = match &res {
+            Ok(_) if set_up => GameStatus::Installing(watch::channel((0, 0)).1),
+            Ok(_) => GameStatus::Ready,
+            Err(ClientError::Canceled) => GameStatus::NotDownloaded,
+            Err(e) => GameStatus::Failed(e.to_string()),
+        };
+        ctx.config.set_status(&mut game, status);
+    }
+    // it might not have gotten to download, like if its archive was cached
+    ctx.config.dequeue_install(id);
+    ctx.config.save();
+    if let Ok(outcome) = &res {
+        ctx.quarantined.remove(&id);
+        tracing::info!(
+            "installed {} in {:?}, downloading {} bytes",
+            game.info.name,
+            outcome.duration,
+            outcome.bytes_downloaded
+        );
+        ctx.config.send_install_outcome(outcome.clone());
+    }
+    res
+}
+
+/// Installs a game from an archive the user already has, like one on a USB drive, instead of
+/// downloading it.
+///
+/// The game's status must already be set to [`GameStatus::Downloading`], which shows the progress
+/// of copying the archive. The archive is checked against the game's passwords, then copied to
+/// where a downloaded one would be and installed with [`install_game`], so the original is left
+/// alone.
+///
+/// # Errors
+/// Returns [`ClientError::BadZipPassword`] if the archive doesn't open with any of the game's
+/// passwords (usually because it's a different game's), or an error if it can't be copied or
+/// installed.
+pub async fn import_archive(
+    ctx: &Ctx,
+    game: Game,
+    source: PathBuf,
+    progress: watch::Sender<(u64, u64)>,
+) -> Result<InstallOutcome> {
+    let id = game.info.id;
+    let archive = ctx.config.archive_path(id);
+    let res = async {
+        tokio::task::spawn_blocking({
+            let source = source.clone();
+            let info = game.info.clone();
+            let default_password = ctx.config.default_zip_password();
+            move || {
+                let candidates = password_candidates(&info, default_password.as_deref());
+                find_password(&source, candidates).map(|_| ())
+            }
+        })
+        .await
+        .expect("archive check panicked")?;
+
+        tracing::info!("importing {source:?} for {}", game.info.name);
+        let staging_dir = ctx.config.staging_dir();
+        tokio::fs::create_dir_all(&staging_dir).await?;
+        check_space(&staging_dir, tokio::fs::metadata(&source).await?.len())?;
+        // copied under another name first so a half copied archive is never installed
+        let copy = archive.with_extension("7z.import");
+        let res = copy_with_progress(&source, &copy, &progress).await;
+        match res {
+            Ok(()) => {
+                tokio::fs::rename(&copy, &archive).await?;
+                ctx.config.record_downloaded_archive(&game.info)
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&copy).await;
+                Err(e)
+            }
+        }
+    }
+    .await;
+
+    if let Err(e) = res {
+        if let Some(mut game) = ctx.config.games().get_mut(&id) {
+            ctx.config
+                .set_status(&mut game, GameStatus::Failed(e.to_string()));
+        }
+        return Err(e);
+    }
+    install_game(ctx, game, progress).await
+}
+
+/// Copies a file, sending how many bytes have been copied to `progress`.
+async fn copy_with_progress(from: &Path, to: &Path, progress: &impl Progress) -> Result<()> {
+    let mut reader = tokio::fs::File::open(from).await?;
+    let total = reader.metadata().await?.len();
+    let mut writer = tokio::io::BufWriter::new(tokio::fs::File::create(to).await?);
+    let mut buf = vec![0; 1024 * 1024];
+    let mut copied = 0;
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n]).await?;
+        copied += n as u64;
+        progress.report(copied, total);
+    }
+    writer.flush().await?;
     Ok(())
 }
+
+/// Restarts any downloads that were interrupted the last time the app was closed.
+///
+/// Installs that were still in the [install queue](crate::Config::install_queue) are restarted
+/// too, in its order. Games in the queue that can't be installed anymore are taken out of it.
+pub fn resume_downloads(ctx: &Ctx) {
+    if let Err(e) = ctx.config.check_games_dir() {
+        tracing::warn!("not resuming downloads: {e}");
+        return;
+    }
+    let games = ctx.config.games();
+    let resumable = |id: &GameId| {
+        games
+            .get(id)
+            .is_some_and(|g| g.status.is_not_downloaded() && g.hooks_error.is_none())
+    };
+    let mut interrupted = Vec::new();
+    for id in ctx.config.install_queue() {
+        if resumable(&id) {
+            interrupted.push(id);
+        } else {
+            ctx.config.dequeue_install(id);
+        }
+    }
+    let partial = games
+        .iter()
+        .filter(|g| g.partial.is_some() && g.status.is_not_downloaded())
+        .map(|g| *g.key())
+        .filter(|id| !interrupted.contains(id))
+        .collect::<Vec<_>>();
+    interrupted.extend(partial);
+
+    for id in interrupted {
+        let (tx, rx) = watch::channel((0, 0));
+        let Some(game) = games.get_mut(&id).map(|mut game| {
+            ctx.config
+                .set_status(&mut game, GameStatus::Downloading(rx));
+            game.clone()
+        }) else {
+            continue;
+        };
+
+        tracing::info!("resuming download: {game:?}");
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let name = game.info.name.clone();
+            if let Err(e) = install_game(&ctx, game, tx).await {
+                tracing::error!("failed to install {name}: {e:#}");
+            }
+        });
+    }
+}
+
+/// How much installing some games would download, found by [`estimate_downloads`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadEstimate {
+    /// The size of each game's archive that could be found. Games whose archive is already on
+    /// this computer are 0.
+    pub sizes: BTreeMap<GameId, u64>,
+    /// The games whose size couldn't be found in time, or whose hosts didn't say.
+    pub unknown: Vec<GameId>,
+    /// The free space where archives are downloaded, if it can be told.
+    pub available: Option<u64>,
+}
+
+impl DownloadEstimate {
+    /// The size of every archive that could be found.
+    pub fn total(&self) -> u64 {
+        self.sizes.values().sum()
+    }
+}
+
+/// Finds how much installing `games` would download before starting any of them.
+///
+/// [`Config::preflight_concurrency`] hosts are asked at a time. Each game's sources are asked in
+/// order until one says, giving up on the game after [`PREFLIGHT_TIMEOUT`].
+///
+/// [`Config::preflight_concurrency`]: crate::Config::preflight_concurrency
+pub async fn estimate_downloads(ctx: &Ctx, games: Vec<GameInfo>) -> DownloadEstimate {
+    let staging_dir = ctx.config.staging_dir();
+    let mut estimate = DownloadEstimate {
+        // the staging folder might not have been made yet, and it's in the games folder then
+        available: crate::available_space(&staging_dir)
+            .or_else(|| crate::available_space(&ctx.config.games_dir())),
+        ..Default::default()
+    };
+    let mut sizes = futures::stream::iter(games)
+        .map(|info| async move {
+            let size = tokio::time::timeout(PREFLIGHT_TIMEOUT, download_size(ctx, &info))
+                .await
+                .unwrap_or_else(|_| {
+                    tracing::warn!("timed out finding the size of {}", info.name);
+                    None
+                });
+            (info.id, size)
+        })
+        .buffer_unordered(ctx.config.preflight_concurrency());
+    while let Some((id, size)) = sizes.next().await {
+        match size {
+            Some(size) => {
+                estimate.sizes.insert(id, size);
+            }
+            None => estimate.unknown.push(id),
+        }
+    }
+    estimate.unknown.sort_unstable();
+    estimate
+}
+
+/// How many bytes installing a game would download: none if its archive is already on this
+/// computer, or what the first of its sources that says gives.
+async fn download_size(ctx: &Ctx, info: &GameInfo) -> Option<u64> {
+    if ctx.config.downloaded_archive(info).is_some() || ctx.config.cached_archive(info).is_some() {
+        return Some(0);
+    }
+    for source in info.sources() {
+        match crate::sources::archive_size(ctx, &source).await {
+            Ok(Some(size)) => return Some(size),
+            Ok(None) => tracing::info!("{source} didn't say how big {} is", info.name),
+            Err(e) => tracing::warn!(
+                "failed to find the size of {} from {source}: {e:#}",
+                info.name
+            ),
+        }
+    }
+    None
+}
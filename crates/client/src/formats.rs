@@ -0,0 +1,250 @@
+//! The kinds of archive games can be extracted from.
+//!
+//! Each kind implements [`Extractor`], which only has to read the archive's files in order. Where
+//! they go, incremental updates, hashing, progress and canceling are handled the same way for
+//! every kind by [`extract_zip_with_password`](crate::download::extract_zip_with_password).
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::download::ArchiveKind;
+use crate::{ClientError, Result};
+
+/// A file in an archive, handed to [`Extractor::for_each_file`] along with its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveFile<'a> {
+    /// Its path in the archive, which can use `/` or `\` separators.
+    pub name: &'a str,
+    /// Its size once extracted, in bytes.
+    pub size: u64,
+    /// The CRC the archive has for it, if it has one.
+    pub crc: Option<u64>,
+}
+
+/// Something that can read a kind of archive.
+pub trait Extractor: Sync {
+    /// Whether archives of this kind can have passwords. If they can't,
+    /// [`find_password`](crate::download::find_password) doesn't read the archive to try any.
+    fn has_passwords(&self) -> bool {
+        true
+    }
+
+    /// The names of the files in the archive, leaving out directories.
+    ///
+    /// # Errors
+    /// Returns an error if the archive can't be read.
+    fn file_names(&self, archive: &Path, password: &str) -> Result<Vec<String>>;
+
+    /// Hands each file in the archive to `f` in order with a reader for its contents, until `f`
+    /// returns `false`. Directories and links are left out.
+    ///
+    /// Archives without passwords ignore `password`.
+    ///
+    /// # Errors
+    /// Returns [`ClientError::BadZipPassword`] if the password is wrong and the archive can tell,
+    /// the first error `f` returns, or another error if the archive can't be read. A wrong
+    /// password the archive can't tell about makes reading a file fail instead.
+    fn for_each_file(
+        &self,
+        archive: &Path,
+        password: &str,
+        f: &mut dyn FnMut(ArchiveFile, &mut dyn Read) -> io::Result<bool>,
+    ) -> Result<()>;
+}
+
+/// A 7zip file, the kind game archives usually are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SevenZip;
+
+/// A zip file, encrypted with `ZipCrypto` or AES if it has a password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Zip;
+
+/// A compressed tar file. Tar files don't have passwords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tar {
+    Gz,
+    Xz,
+}
+
+/// Every kind of archive that can be extracted, with what reads it.
+const EXTRACTORS: &[(ArchiveKind, &dyn Extractor)] = &[
+    (ArchiveKind::SevenZip, &SevenZip),
+    (ArchiveKind::Zip, &Zip),
+    (ArchiveKind::TarGz, &Tar::Gz),
+    (ArchiveKind::TarXz, &Tar::Xz),
+];
+
+/// What reads archives of `kind`, or `None` if they can't be extracted.
+pub fn extractor(kind: ArchiveKind) -> Option<&'static dyn Extractor> {
+    EXTRACTORS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, extractor)| *extractor)
+}
+
+impl Extractor for SevenZip {
+    fn file_names(&self, archive: &Path, password: &str) -> Result<Vec<String>> {
+        let sz = sevenz_rust::SevenZReader::open(archive, password.into())?;
+        Ok(sz
+            .archive()
+            .files
+            .iter()
+            .filter(|f| !f.is_directory())
+            .map(|f| f.name().to_owned())
+            .collect())
+    }
+
+    fn for_each_file(
+        &self,
+        archive: &Path,
+        password: &str,
+        f: &mut dyn FnMut(ArchiveFile, &mut dyn Read) -> io::Result<bool>,
+    ) -> Result<()> {
+        let mut sz = sevenz_rust::SevenZReader::open(archive, password.into())?;
+        sz.for_each_entries(|entry, reader| {
+            if entry.is_directory() {
+                return Ok(true);
+            }
+            let file = ArchiveFile {
+                name: entry.name(),
+                size: entry.size(),
+                crc: entry.has_crc.then_some(entry.crc),
+            };
+            Ok(f(file, reader)?)
+        })?;
+        Ok(())
+    }
+}
+
+impl Zip {
+    fn open(archive: &Path) -> Result<zip::ZipArchive<BufReader<File>>> {
+        Ok(zip::ZipArchive::new(BufReader::new(File::open(archive)?))?)
+    }
+}
+
+impl Extractor for Zip {
+    fn file_names(&self, archive: &Path, _password: &str) -> Result<Vec<String>> {
+        Ok(Self::open(archive)?
+            .file_names()
+            .filter(|name| !name.ends_with(['/', '\\']))
+            .map(ToOwned::to_owned)
+            .collect())
+    }
+
+    fn for_each_file(
+        &self,
+        archive: &Path,
+        password: &str,
+        f: &mut dyn FnMut(ArchiveFile, &mut dyn Read) -> io::Result<bool>,
+    ) -> Result<()> {
+        let mut zip = Self::open(archive)?;
+        for i in 0..zip.len() {
+            // the password is ignored for files that aren't encrypted
+            let mut entry = zip
+                .by_index_decrypt(i, password.as_bytes())?
+                .map_err(|_| ClientError::BadZipPassword)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let name = entry.name().to_owned();
+            let file = ArchiveFile {
+                name: &name,
+                size: entry.size(),
+                crc: (!leaves_out_crc(entry.extra_data())).then(|| u64::from(entry.crc32())),
+            };
+            if !f(file, &mut entry)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a zip file's extra field says it's AES encrypted the AE-2 way, which leaves out its
+/// CRC and stores 0 instead.
+fn leaves_out_crc(mut extra: &[u8]) -> bool {
+    const AES_HEADER_ID: u16 = 0x9901;
+    while let [id_lo, id_hi, len_lo, len_hi, rest @ ..] = extra {
+        let len = usize::from(u16::from_le_bytes([*len_lo, *len_hi]));
+        let Some(data) = rest.get(..len) else {
+            break;
+        };
+        if u16::from_le_bytes([*id_lo, *id_hi]) == AES_HEADER_ID {
+            // the vendor version: AE-1 keeps the CRC
+            return data.get(..2) == Some(&[2, 0][..]);
+        }
+        extra = &rest[len..];
+    }
+    false
+}
+
+impl Tar {
+    fn open(self, archive: &Path) -> io::Result<tar::Archive<Box<dyn Read>>> {
+        Ok(self.decode(BufReader::new(File::open(archive)?)))
+    }
+
+    fn decode<'a>(self, reader: impl Read + 'a) -> tar::Archive<Box<dyn Read + 'a>> {
+        let decoder: Box<dyn Read + 'a> = match self {
+            Self::Gz => Box::new(flate2::read::GzDecoder::new(reader)),
+            Self::Xz => Box::new(xz2::read::XzDecoder::new(reader)),
+        };
+        tar::Archive::new(decoder)
+    }
+
+    /// [`Extractor::for_each_file`], reading the archive from `reader` instead of a file, like
+    /// while it's downloading.
+    ///
+    /// # Errors
+    /// Returns the first error `f` returns, or another error if the archive can't be read.
+    pub fn for_each_file_in(
+        self,
+        reader: impl Read,
+        f: &mut dyn FnMut(ArchiveFile, &mut dyn Read) -> io::Result<bool>,
+    ) -> Result<()> {
+        for entry in self.decode(reader).entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = String::from_utf8_lossy(&entry.path_bytes()).into_owned();
+            let file = ArchiveFile {
+                name: &name,
+                size: entry.size(),
+                crc: None,
+            };
+            if !f(file, &mut entry)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Extractor for Tar {
+    fn has_passwords(&self) -> bool {
+        false
+    }
+
+    /// Tar files don't have a list of their files, so this reads the whole archive.
+    fn file_names(&self, archive: &Path, _password: &str) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in self.open(archive)?.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() {
+                names.push(String::from_utf8_lossy(&entry.path_bytes()).into_owned());
+            }
+        }
+        Ok(names)
+    }
+
+    fn for_each_file(
+        &self,
+        archive: &Path,
+        _password: &str,
+        f: &mut dyn FnMut(ArchiveFile, &mut dyn Read) -> io::Result<bool>,
+    ) -> Result<()> {
+        self.for_each_file_in(BufReader::new(File::open(archive)?), f)
+    }
+}
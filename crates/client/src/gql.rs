@@ -1,9 +1,9 @@
-use bramlett::{Ctx, Game, GameStatus};
+use bramlett::{py::HookStatus, sources::LinkStrategy, Ctx, ErrorStage, Game, GameStatus};
 use common::GameId;
 use dashmap::DashMap;
 use juniper::{graphql_object, EmptySubscription, FieldResult, GraphQLEnum, RootNode};
-use std::sync::Arc;
-use tokio::{process::Command, sync::watch};
+use std::{path::PathBuf, process::Stdio, sync::Arc, time::SystemTime};
+use tokio::sync::watch;
 
 #[derive(Debug, Copy, Clone, thiserror::Error)]
 pub enum GraphQLError {
@@ -13,6 +13,16 @@ pub enum GraphQLError {
     AlreadyDownloaded,
     #[error("game running, downloading, installing, or not downloaded")]
     NotDeleted,
+    #[error("game's hooks are broken; it can't be installed or run until they're fixed")]
+    BrokenHooks,
+    #[error("game isn't installed, or is running")]
+    NotInstalled,
+    #[error("game isn't being installed")]
+    NotInstalling,
+    #[error("game is running, downloading or installing")]
+    NotMoved,
+    #[error("the saves folder is in the game's folder, so reinstalling would delete the saves")]
+    SavesInGameDir,
 }
 
 pub struct GraphQLGame(pub GameId, Arc<DashMap<GameId, Game>>);
@@ -49,9 +59,333 @@ impl GraphQLGame {
     pub fn icon(&self) -> FieldResult<String> {
         Ok(self.get()?.info.icon)
     }
+    pub fn description(&self) -> FieldResult<Option<String>> {
+        Ok(self.get()?.info.description)
+    }
+    pub fn changelog(&self) -> FieldResult<Option<String>> {
+        Ok(self.get()?.info.changelog)
+    }
+    /// A web page about the game, like one explaining its controls, if it has one that can be
+    /// opened
+    pub fn homepage(&self) -> FieldResult<Option<String>> {
+        Ok(self
+            .get()?
+            .info
+            .homepage
+            .filter(|url| bramlett::web_page(url).is_ok()))
+    }
+    /// Lowercase categories, like "puzzle"
+    pub fn tags(&self) -> FieldResult<Vec<String>> {
+        Ok(self.get()?.info.tags)
+    }
+    /// Why the game's hooks don't compile, if they don't
+    pub fn hooks_error(&self) -> FieldResult<Option<String>> {
+        Ok(self.get()?.hooks_error)
+    }
+    /// Whether the game's archive is already downloaded or cached, so installing it only has to
+    /// extract it
+    pub fn archive_downloaded(&self, ctx: &Ctx) -> bool {
+        self.get().is_ok_and(|game| {
+            ctx.config.downloaded_archive(&game.info).is_some()
+                || ctx.config.cached_archive(&game.info).is_some()
+        })
+    }
+    /// The folder the game is installed in instead of the games folder, if it has one
+    pub fn install_dir(&self, ctx: &Ctx) -> Option<String> {
+        ctx.config
+            .install_dir(self.0)
+            .map(|dir| dir.to_string_lossy().into_owned())
+    }
+    /// The last lines the game printed the last time it was run
+    pub fn output(&self, ctx: &Ctx) -> Vec<String> {
+        ctx.game_output
+            .get(&self.0)
+            .map(|output| output.lines())
+            .unwrap_or_default()
+    }
+    /// Whether the game has exited since it was last run
+    pub fn exited(&self, ctx: &Ctx) -> bool {
+        ctx.game_output
+            .get(&self.0)
+            .is_some_and(|output| output.has_exited())
+    }
+    /// The exit code the game had the last time it was run, if it's exited with one
+    pub fn exit_code(&self, ctx: &Ctx) -> Option<i32> {
+        ctx.game_output
+            .get(&self.0)
+            .and_then(|output| output.exit_code())
+    }
+    /// The name of the hook the game is running, like `post_install`, if it's running one
+    pub fn running_hook(&self, ctx: &Ctx) -> Option<&'static str> {
+        match ctx.hooks.get(&self.0).as_deref() {
+            Some(HookStatus::Running(hook)) => Some(hook.func()),
+            _ => None,
+        }
+    }
+    /// Why the last hook the game ran failed, if it did
+    pub fn hook_failure(&self, ctx: &Ctx) -> Option<String> {
+        match ctx.hooks.get(&self.0).as_deref() {
+            Some(HookStatus::Failed { hook, error }) => Some(format!("{}: {error}", hook.func())),
+            _ => None,
+        }
+    }
+    /// The full path of the executable running the game starts
+    pub fn exe_path(&self, ctx: &Ctx) -> FieldResult<String> {
+        let launch = ctx.config.launch(&self.get()?.info);
+        Ok(launch.exe.display().to_string())
+    }
+    /// The folder the game is run in
+    pub fn working_dir(&self, ctx: &Ctx) -> FieldResult<String> {
+        let launch = ctx.config.launch(&self.get()?.info);
+        Ok(launch.working_dir.display().to_string())
+    }
+    /// Percent of the CPU the game and the processes it started are using, while it's running
+    pub fn cpu_usage(&self, ctx: &Ctx) -> Option<f64> {
+        ctx.usage.get(&self.0).map(|usage| f64::from(usage.cpu))
+    }
+    /// Bytes of memory the game and the processes it started are using, while it's running
+    pub fn memory_usage(&self, ctx: &Ctx) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        ctx.usage.get(&self.0).map(|usage| usage.memory as f64)
+    }
+    /// Where the game's download resumed from in bytes, if it picked up an interrupted one
+    pub fn resumed_at(&self, ctx: &Ctx) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        ctx.resumed_from.get(&self.0).map(|at| *at as f64)
+    }
+    /// Whether the game's executable was missing or blocked the last time it was run, which is
+    /// usually an antivirus quarantining it
+    pub fn quarantined(&self, ctx: &Ctx) -> bool {
+        ctx.quarantined.contains(&self.0)
+    }
+    /// Whether the game has made a save folder yet
+    pub fn save_dir_exists(&self, ctx: &Ctx) -> bool {
+        ctx.config.save_dir(self.0).is_dir()
+    }
+    /// The size of the game's save folder in bytes, or `null` while it's being measured
+    pub fn save_size(&self, ctx: &Ctx) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        if let Some(size) = ctx.save_sizes.get(&self.0) {
+            return Some(*size as f64);
+        }
+        let id = self.0;
+        // the list is polled more often than a big folder can be measured
+        if !ctx.measuring_saves.insert(id) {
+            return None;
+        }
+        let dir = ctx.config.save_dir(id);
+        let sizes = ctx.save_sizes.clone();
+        let measuring = ctx.measuring_saves.clone();
+        tokio::task::spawn_blocking(move || {
+            sizes.insert(id, bramlett::cleanup::size_of(&dir));
+            measuring.remove(&id);
+        });
+        None
+    }
     pub fn status(&self) -> FieldResult<GraphQLGameStatus> {
         Ok(GraphQLGameStatus::from(self.get()?.status))
     }
+    /// Whether the game is kept on the version it's on when the game list changes
+    pub fn pinned(&self, ctx: &Ctx) -> bool {
+        ctx.config.is_pinned(self.0)
+    }
+    /// Whether the server has a different version of the game than the pinned one
+    pub fn update_held(&self, ctx: &Ctx) -> bool {
+        ctx.config.held_update(self.0).is_some()
+    }
+    /// The last errors the game had, newest first
+    pub fn errors(&self, ctx: &Ctx) -> Vec<GraphQLGameError> {
+        let mut errors = ctx.config.error_history(self.0);
+        errors.reverse();
+        errors.into_iter().map(GraphQLGameError).collect()
+    }
+}
+
+pub struct GraphQLGameError(bramlett::GameError);
+
+#[graphql_object(context = Ctx)]
+impl GraphQLGameError {
+    /// When the error happened, in milliseconds since the Unix epoch
+    pub fn time(&self) -> f64 {
+        self.0
+            .time
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+    }
+    pub const fn stage(&self) -> GraphQLErrorStage {
+        match self.0.stage {
+            ErrorStage::Download => GraphQLErrorStage::Download,
+            ErrorStage::Install => GraphQLErrorStage::Install,
+            ErrorStage::Run => GraphQLErrorStage::Run,
+        }
+    }
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+}
+
+/// What the app has downloaded and installed since it started, for monitoring
+pub struct GraphQLStats;
+
+#[graphql_object(context = Ctx)]
+impl GraphQLStats {
+    /// Bytes downloaded, including downloads that failed partway
+    pub fn bytes_downloaded(&self, ctx: &Ctx) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let bytes = ctx.stats.bytes_downloaded() as f64;
+        bytes
+    }
+    pub fn active_downloads(&self, ctx: &Ctx) -> i32 {
+        i32::try_from(ctx.config.activity().downloading).unwrap_or(i32::MAX)
+    }
+    pub fn active_installs(&self, ctx: &Ctx) -> i32 {
+        i32::try_from(ctx.config.activity().installing).unwrap_or(i32::MAX)
+    }
+    pub fn installs_completed(&self, ctx: &Ctx) -> i32 {
+        i32::try_from(ctx.stats.installs_completed()).unwrap_or(i32::MAX)
+    }
+    /// Installs that failed, not counting canceled ones
+    pub fn installs_failed(&self, ctx: &Ctx) -> i32 {
+        i32::try_from(ctx.stats.installs_failed()).unwrap_or(i32::MAX)
+    }
+}
+
+/// The last check of every installed game's files
+pub struct GraphQLLibraryAudit(bramlett::manifest::LibraryAudit);
+
+#[graphql_object(context = Ctx)]
+impl GraphQLLibraryAudit {
+    /// Whether the audit is still checking games
+    pub const fn running(&self) -> bool {
+        self.0.running
+    }
+    /// How many games have been checked
+    pub fn checked(&self) -> i32 {
+        i32::try_from(self.0.audits.len()).unwrap_or(i32::MAX)
+    }
+    /// How many games are being checked
+    pub fn total(&self) -> i32 {
+        i32::try_from(self.0.total).unwrap_or(i32::MAX)
+    }
+    /// The games with missing or damaged files, which need to be repaired
+    pub fn games(&self) -> Vec<GraphQLGameAudit> {
+        self.0
+            .audits
+            .iter()
+            .filter(|(_, audit)| audit.needs_repair())
+            .map(|(id, audit)| GraphQLGameAudit(*id, audit.clone()))
+            .collect()
+    }
+    /// How many files are missing or damaged, in every game
+    pub fn damaged_files(&self) -> i32 {
+        let damaged: usize = self
+            .0
+            .audits
+            .values()
+            .map(|audit| audit.damaged.len())
+            .sum();
+        i32::try_from(damaged).unwrap_or(i32::MAX)
+    }
+    /// About how many bytes repairing every game that needs it downloads (before compression)
+    pub fn repair_size(&self) -> f64 {
+        let size: u64 = self.games().iter().map(|game| game.1.size).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let size = size as f64;
+        size
+    }
+}
+
+/// A game an audit found missing or damaged files in
+pub struct GraphQLGameAudit(GameId, bramlett::manifest::Audit);
+
+#[graphql_object(context = Ctx)]
+impl GraphQLGameAudit {
+    pub const fn game(&self) -> GameId {
+        self.0
+    }
+    pub fn name(&self, ctx: &Ctx) -> Option<String> {
+        ctx.config
+            .games()
+            .get(&self.0)
+            .map(|game| game.info.name.clone())
+    }
+    /// The missing or damaged files, relative to the game's folder
+    pub fn damaged_files(&self) -> Vec<String> {
+        self.1.damaged.clone()
+    }
+    /// About how many bytes repairing the game downloads (before compression)
+    pub const fn repair_size(&self) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let size = self.1.size as f64;
+        size
+    }
+}
+
+/// How much installing some games would download
+pub struct GraphQLDownloadEstimate(bramlett::download::DownloadEstimate);
+
+#[graphql_object(context = Ctx)]
+impl GraphQLDownloadEstimate {
+    /// The total size of the games whose size was found, in bytes
+    pub fn total_bytes(&self) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let total = self.0.total() as f64;
+        total
+    }
+    /// The games whose size couldn't be found
+    pub fn unknown(&self) -> Vec<GameId> {
+        self.0.unknown.clone()
+    }
+    /// The free space where archives are downloaded, in bytes, if it can be told
+    pub fn available_bytes(&self) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        self.0.available.map(|available| available as f64)
+    }
+}
+
+/// Where one of a game's sources really downloads it from, or why that couldn't be found
+pub struct GraphQLDownloadLink(
+    common::GameSource,
+    bramlett::Result<bramlett::sources::DownloadLink>,
+);
+
+#[graphql_object(context = Ctx)]
+impl GraphQLDownloadLink {
+    pub fn source(&self) -> String {
+        self.0.to_string()
+    }
+    pub fn url(&self) -> Option<&str> {
+        self.1.as_ref().ok().map(|link| link.url.as_str())
+    }
+    /// How the URL was found
+    pub fn strategy(&self) -> Option<GraphQLLinkStrategy> {
+        self.1.as_ref().ok().map(|link| match link.strategy {
+            LinkStrategy::Direct => GraphQLLinkStrategy::Direct,
+            LinkStrategy::DriveUnconfirmed => GraphQLLinkStrategy::DriveUnconfirmed,
+            LinkStrategy::DriveFormAction => GraphQLLinkStrategy::DriveFormAction,
+            LinkStrategy::DriveHiddenInputs => GraphQLLinkStrategy::DriveHiddenInputs,
+        })
+    }
+    pub fn error(&self) -> Option<String> {
+        self.1.as_ref().err().map(ToString::to_string)
+    }
+}
+
+/// How a download URL was found
+#[derive(Debug, Copy, Clone, PartialEq, Eq, GraphQLEnum)]
+pub enum GraphQLLinkStrategy {
+    Direct,
+    DriveUnconfirmed,
+    DriveFormAction,
+    DriveHiddenInputs,
+}
+
+/// What was being done with a game when it failed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, GraphQLEnum)]
+pub enum GraphQLErrorStage {
+    Download,
+    Install,
+    Run,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, GraphQLEnum)]
@@ -61,6 +395,7 @@ pub enum GraphQLGameStatusInner {
     Installing,
     Running,
     Ready,
+    Failed,
 }
 
 #[derive(GraphQLEnum)]
@@ -74,6 +409,7 @@ pub struct GraphQLGameStatus {
     pub status: GraphQLGameStatusInner,
     #[serde(skip)]
     pub progress: Option<watch::Receiver<(u64, u64)>>,
+    pub error: Option<String>,
 }
 
 impl From<GameStatus> for GraphQLGameStatus {
@@ -82,22 +418,32 @@ impl From<GameStatus> for GraphQLGameStatus {
             GameStatus::NotDownloaded => Self {
                 status: GraphQLGameStatusInner::NotDownloaded,
                 progress: None,
+                error: None,
             },
             GameStatus::Downloading(prog) => Self {
                 status: GraphQLGameStatusInner::Downloading,
                 progress: Some(prog),
+                error: None,
             },
             GameStatus::Installing(prog) => Self {
                 status: GraphQLGameStatusInner::Installing,
                 progress: Some(prog),
+                error: None,
             },
             GameStatus::Running => Self {
                 status: GraphQLGameStatusInner::Running,
                 progress: None,
+                error: None,
             },
             GameStatus::Ready => Self {
                 status: GraphQLGameStatusInner::Ready,
                 progress: None,
+                error: None,
+            },
+            GameStatus::Failed(error) => Self {
+                status: GraphQLGameStatusInner::Failed,
+                progress: None,
+                error: Some(error),
             },
         }
     }
@@ -108,6 +454,14 @@ impl GraphQLGameStatus {
     pub const fn status(&self) -> GraphQLGameStatusInner {
         self.status
     }
+    /// Why the last install failed, if it did
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+    /// Whether the game is downloading, but downloads are paused
+    pub fn paused(&self, ctx: &Ctx) -> bool {
+        self.status == GraphQLGameStatusInner::Downloading && ctx.pause.is_paused()
+    }
     /// Progress in megabytes
     pub fn progress(&self) -> std::option::Option<[i32; 2]> {
         #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
@@ -125,6 +479,14 @@ impl GraphQLGameStatus {
                 }
             })
     }
+    /// Exact progress: bytes while downloading, files while installing
+    pub fn exact_progress(&self) -> std::option::Option<[f64; 2]> {
+        #[allow(clippy::cast_precision_loss)]
+        self.progress
+            .as_ref()
+            .map(|p| *p.borrow())
+            .map(|(num, denom)| [num as f64, denom as f64])
+    }
 }
 
 pub struct Query;
@@ -144,6 +506,169 @@ impl Query {
         games.sort_unstable_by_key(|g| g.0); // needed b/c DashMap doesn't guarantee order
         games
     }
+    /// The UI language, e.g. `en-US`
+    pub fn locale(ctx: &Ctx) -> String {
+        ctx.config.locale()
+    }
+    /// Whether the games are shown as a list instead of a grid
+    pub fn list_view(ctx: &Ctx) -> bool {
+        ctx.config.list_view()
+    }
+    /// Whether a game's files are checked before it's run
+    pub fn verify_before_run(ctx: &Ctx) -> bool {
+        ctx.config.verify_before_run()
+    }
+    /// Whether details most people don't need, like how games are launched, are shown
+    pub fn advanced(ctx: &Ctx) -> bool {
+        ctx.config.advanced()
+    }
+    pub fn games_dir(ctx: &Ctx) -> String {
+        ctx.config.games_dir().display().to_string()
+    }
+    /// Whether the games directory looks like it can be written to
+    pub fn games_dir_available(ctx: &Ctx) -> bool {
+        ctx.config.games_dir_available()
+    }
+    pub fn saves_dir(ctx: &Ctx) -> String {
+        ctx.config.saves_dir().display().to_string()
+    }
+    /// The folder archives are downloaded to before they're installed
+    pub fn staging_dir(ctx: &Ctx) -> String {
+        ctx.config.staging_dir().display().to_string()
+    }
+    /// The folder archives are downloaded to instead of the games folder, if there is one
+    pub fn custom_staging_dir(ctx: &Ctx) -> Option<String> {
+        ctx.config
+            .custom_staging_dir()
+            .map(|dir| dir.display().to_string())
+    }
+    /// A saves folder in the user's OneDrive, if it's set up, to offer in the wizard
+    pub fn onedrive_saves_dir() -> Option<String> {
+        bramlett::Config::onedrive_saves_dir().map(|dir| dir.display().to_string())
+    }
+    /// Whether the first run wizard has been finished
+    pub fn setup_complete(ctx: &Ctx) -> bool {
+        ctx.config.setup_complete()
+    }
+    /// How many bytes of leftover files the clean up would remove
+    pub fn orphaned_bytes(ctx: &Ctx) -> f64 {
+        let orphans = bramlett::cleanup::find_orphans(&ctx.config);
+        #[allow(clippy::cast_precision_loss)]
+        let bytes = orphans
+            .iter()
+            .map(|p| bramlett::cleanup::size_of(p))
+            .sum::<u64>() as f64;
+        bytes
+    }
+    /// The proxy downloads go through, if one is set
+    pub fn proxy(ctx: &Ctx) -> Option<String> {
+        ctx.config.proxy()
+    }
+    /// A bug report with the recent logs and the config, for the user to send
+    pub fn bug_report(ctx: &Ctx, error: Option<String>) -> String {
+        bramlett::logs::bug_report(&ctx.config, error.as_deref())
+    }
+    /// The games waiting for their download to start, first to start first
+    pub fn install_queue(ctx: &Ctx) -> Vec<GameId> {
+        ctx.config.install_queue()
+    }
+    /// Where each of a game's sources really downloads it from, without downloading it, to tell
+    /// whether a failing download is the host's fault
+    pub async fn download_links(ctx: &Ctx, game: GameId) -> FieldResult<Vec<GraphQLDownloadLink>> {
+        let sources = ctx
+            .config
+            .games()
+            .get(&game)
+            .ok_or(GraphQLError::NotFound)?
+            .info
+            .sources();
+        let mut links = Vec::new();
+        for source in sources {
+            let link = bramlett::sources::resolve_link(ctx, &source).await;
+            links.push(GraphQLDownloadLink(source, link));
+        }
+        Ok(links)
+    }
+    /// How much installing the games would download, asking their hosts a few at a time so it's
+    /// known before installing them all
+    pub async fn download_estimate(
+        ctx: &Ctx,
+        games: Vec<GameId>,
+    ) -> FieldResult<GraphQLDownloadEstimate> {
+        let infos = {
+            let all = ctx.config.games();
+            games
+                .iter()
+                .map(|id| Ok(all.get(id).ok_or(GraphQLError::NotFound)?.info.clone()))
+                .collect::<FieldResult<Vec<_>>>()?
+        };
+        let estimate = bramlett::download::estimate_downloads(ctx, infos).await;
+        Ok(GraphQLDownloadEstimate(estimate))
+    }
+    /// When the game list was last updated, in milliseconds since the Unix epoch
+    pub fn game_list_updated(ctx: &Ctx) -> Option<f64> {
+        ctx.config
+            .game_list_updated()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs_f64() * 1000.0)
+    }
+    /// When downloads can start again, in milliseconds since the Unix epoch, if it's outside the
+    /// download window. Until then, queued downloads wait.
+    pub fn download_window_opens(ctx: &Ctx) -> Option<f64> {
+        let window = ctx.config.download_window()?;
+        let now = bramlett::schedule::now();
+        if window.is_open(now) {
+            return None;
+        }
+        let opens = std::time::SystemTime::now() + window.next_change(now)?;
+        opens
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs_f64() * 1000.0)
+    }
+    /// The games in the last game list that couldn't be read, and why
+    pub fn skipped_games(ctx: &Ctx) -> Vec<String> {
+        ctx.config.skipped_games()
+    }
+    /// How many times a download that fails partway is retried
+    pub fn download_retries(ctx: &Ctx) -> i32 {
+        i32::try_from(ctx.config.download_retries()).unwrap_or(i32::MAX)
+    }
+    /// How many downloads and extractions can run at once
+    pub fn max_concurrent_work(ctx: &Ctx) -> i32 {
+        i32::try_from(ctx.config.max_concurrent_work()).unwrap_or(i32::MAX)
+    }
+    /// How many hosts are asked for a game's size at once before installing several games
+    pub fn preflight_concurrency(ctx: &Ctx) -> i32 {
+        i32::try_from(ctx.config.preflight_concurrency()).unwrap_or(i32::MAX)
+    }
+    /// Whether downloaded archives are kept after installing, so reinstalling doesn't download
+    /// them again
+    pub fn keep_archives(ctx: &Ctx) -> bool {
+        ctx.config.keep_archives()
+    }
+    /// The size of the kept archives, in bytes
+    pub fn archive_cache_bytes(ctx: &Ctx) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let bytes = ctx.config.archive_cache_size() as f64;
+        bytes
+    }
+    /// The most bytes of archives that are kept, or `null` for no limit
+    pub fn archive_cache_limit(ctx: &Ctx) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        ctx.config.archive_cache_limit().map(|limit| limit as f64)
+    }
+    pub fn downloads_paused(ctx: &Ctx) -> bool {
+        ctx.pause.is_paused()
+    }
+    /// What the app has downloaded and installed since it started
+    pub const fn stats() -> GraphQLStats {
+        GraphQLStats
+    }
+    /// The last check of every installed game's files
+    pub fn library_audit(ctx: &Ctx) -> GraphQLLibraryAudit {
+        GraphQLLibraryAudit(ctx.library_audit.lock().unwrap().clone())
+    }
     pub fn firefox() -> FirefoxStatus {
         if bramlett::firefox::get_profile_path().is_some() {
             FirefoxStatus::Ready
@@ -160,77 +685,522 @@ pub enum FirefoxStatus {
 
 pub struct Mutation;
 
+/// Marks a game as downloading if it can be installed, returning it and the sender for its
+/// progress.
+fn start_install(ctx: &Ctx, id: GameId) -> FieldResult<(Game, watch::Sender<(u64, u64)>)> {
+    ctx.config.check_games_dir()?;
+    let games = ctx.config.games();
+    let mut game = games.get_mut(&id).ok_or(GraphQLError::NotFound)?;
+    if matches!(
+        game.status,
+        GameStatus::Downloading(_) | GameStatus::Installing(_)
+    ) {
+        return Err(GraphQLError::AlreadyDownloaded.into());
+    }
+    if game.hooks_error.is_some() {
+        return Err(GraphQLError::BrokenHooks.into());
+    }
+    let (tx, rx) = watch::channel((0, 0));
+    ctx.config.set_status(&mut game, GameStatus::Downloading(rx));
+    Ok((game.clone(), tx))
+}
+
 #[graphql_object(context = Ctx)]
 impl Mutation {
     pub fn download(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
-        let games = ctx.config.games();
-        let (tx, rx) = watch::channel((0, 0));
-        let game = {
-            let mut game = games.get_mut(&game).ok_or(GraphQLError::NotFound)?;
-            if matches!(
-                game.status,
-                GameStatus::Downloading(_) | GameStatus::Installing(_)
-            ) {
-                return Err(GraphQLError::AlreadyDownloaded.into());
-            }
-            game.status = GameStatus::Downloading(rx);
-            game.clone()
-        };
+        let (game, tx) = start_install(ctx, game)?;
         tracing::info!("downloading game: {game:?}");
         tokio::spawn({
             let ctx = ctx.clone();
             async move {
-                let bytes = bramlett::download::download_game(game.clone(), ctx.clone(), tx)
-                    .await
-                    .unwrap();
-                tracing::info!("downloaded game: {game:?}; extracting...");
-                tokio::task::spawn_blocking(move || {
-                    let (tx, rx) = watch::channel((0, 0));
-                    games.get_mut(&game.info.id).unwrap().status = GameStatus::Installing(rx);
-                    bramlett::download::extract_zip_with_password(
-                        &bytes,
-                        &ctx.config.game_dir(game.info.id),
-                        "game",
-                        tx,
-                    )
-                    .unwrap();
-
-                    games.get_mut(&game.info.id).unwrap().status = GameStatus::Ready;
-                    ctx.config.save().unwrap();
-                });
+                let name = game.info.name.clone();
+                if let Err(e) = bramlett::download::install_game(&ctx, game, tx).await {
+                    tracing::error!("failed to install {name}: {e:#}");
+                }
+            }
+        });
+        Ok(Void)
+    }
+
+    /// Installs the games, downloading them in the given order. Games that can't be installed
+    /// right now are skipped. Returns how many are being installed.
+    pub fn download_all(ctx: &Ctx, games: Vec<GameId>) -> FieldResult<i32> {
+        let mut started = 0;
+        for id in games {
+            let (game, tx) = match start_install(ctx, id) {
+                Ok(install) => install,
+                Err(e) => {
+                    tracing::info!("not installing game {id}: {}", e.message());
+                    continue;
+                }
+            };
+            // queued right away, so they download in order whichever starts first
+            ctx.config.enqueue_install(id);
+            tracing::info!("downloading game: {game:?}");
+            tokio::spawn({
+                let ctx = ctx.clone();
+                async move {
+                    let name = game.info.name.clone();
+                    if let Err(e) = bramlett::download::install_game(&ctx, game, tx).await {
+                        tracing::error!("failed to install {name}: {e:#}");
+                    }
+                }
+            });
+            started += 1;
+        }
+        Ok(started)
+    }
+
+    /// Installs a game from an archive that's already on this computer (like on a USB drive)
+    /// instead of downloading it. The archive itself is left alone.
+    pub fn import_archive(ctx: &Ctx, game: GameId, path: String) -> FieldResult<VoidEnum> {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            return Err(bramlett::ClientError::ArchiveNotAFile(path).into());
+        }
+        let (game, tx) = start_install(ctx, game)?;
+        tokio::spawn({
+            let ctx = ctx.clone();
+            async move {
+                let name = game.info.name.clone();
+                if let Err(e) = bramlett::download::import_archive(&ctx, game, path, tx).await {
+                    tracing::error!("failed to import {name}: {e:#}");
+                }
             }
         });
         Ok(Void)
     }
 
+    /// Stops installing a game. The downloaded archive is kept, so installing it again doesn't
+    /// download it again.
+    pub fn cancel_install(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
+        if ctx.cancel_install(game) {
+            tracing::info!("canceling install of {game}");
+            Ok(Void)
+        } else {
+            Err(GraphQLError::NotInstalling.into())
+        }
+    }
+
     pub fn run(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
         let games = ctx.config.games();
         let game = {
             let mut game = games.get_mut(&game).ok_or(GraphQLError::NotFound)?;
-            game.status = GameStatus::Running;
+            if game.hooks_error.is_some() {
+                return Err(GraphQLError::BrokenHooks.into());
+            }
+            let launch = ctx.config.launch(&game.info);
+            if let Err(e) = bramlett::antivirus::check_exe(&launch.exe) {
+                tracing::warn!("not running {}: {e}", game.info.name);
+                if matches!(e, bramlett::ClientError::ExeQuarantined(_)) {
+                    ctx.quarantined.insert(game.info.id);
+                }
+                ctx.config
+                    .record_error(game.info.id, ErrorStage::Run, e.to_string());
+                return Err(e.into());
+            }
+            ctx.quarantined.remove(&game.info.id);
+            if ctx.config.verify_before_run() {
+                let game_dir = ctx.config.game_dir(game.info.id);
+                if let Err(e) = bramlett::manifest::verify_install(&game_dir, &game.info.exe) {
+                    tracing::warn!("not running {}: {e}", game.info.name);
+                    ctx.config
+                        .set_status(&mut game, GameStatus::Failed(e.to_string()));
+                    return Err(e.into());
+                }
+            }
+            if let Err(e) = ctx.config.check_launch(game.info.id, &launch) {
+                tracing::warn!("{}: {e}", game.info.name);
+                ctx.config
+                    .record_error(game.info.id, ErrorStage::Run, e.to_string());
+                return Err(e.into());
+            }
+            ctx.config.set_status(&mut game, GameStatus::Running);
             game.clone()
         };
         tracing::info!("running game: {game:?}");
         let ctx = ctx.clone();
         tokio::spawn(async move {
-            let game_dir = ctx.config.game_dir(game.info.id);
-            let exe = game_dir.join(&game.info.exe);
+            let launch = ctx.config.launch(&game.info);
+            let exe = &launch.exe;
 
-            if let Ok(mut child) = Command::new(&exe)
-                .current_dir(exe.parent().unwrap_or(&game_dir))
-                .spawn()
-            {
-                let _ = child.wait().await;
+            let output = bramlett::logs::GameOutput::default();
+            ctx.game_output.insert(game.info.id, output.clone());
+            if launch.elevated && !cfg!(windows) {
+                tracing::warn!("{} needs to run as administrator", game.info.name);
+                output.push(
+                    "this game needs to run as administrator, which is only asked for on \
+                     Windows -- running it normally"
+                        .to_owned(),
+                );
             }
+            // an older game can need them to show anything, so it's still run without them
+            if let Err(e) = bramlett::compat::apply(exe, &game.info.compat) {
+                tracing::warn!(
+                    "failed to apply {}'s compatibility settings: {e}",
+                    game.info.name
+                );
+                output.push(format!(
+                    "couldn't apply the game's compatibility settings: {e}"
+                ));
+            }
+
+            // the hooks sync the game's saves with its saves folder
+            bramlett::py::run_with_hooks(&ctx, game.info.id, async {
+                let limits = ctx.config.sandbox(game.info.id);
+                if launch.elevated && !limits.is_unlimited() && cfg!(windows) {
+                    // the elevated game is started by Windows, outside of PowerShell's job
+                    tracing::warn!(
+                        "{} runs as administrator, so it isn't limited",
+                        game.info.name
+                    );
+                }
+                let job = match bramlett::sandbox::job(&limits) {
+                    Ok(job) => job,
+                    Err(e) => {
+                        // the limits are there to keep games in check, so it doesn't run without
+                        // them
+                        let message = format!("couldn't limit what the game can use: {e}");
+                        tracing::error!("{}: {message}", game.info.name);
+                        ctx.config
+                            .record_error(game.info.id, ErrorStage::Run, message.clone());
+                        output.push(message);
+                        output.exited(None);
+                        return;
+                    }
+                };
+
+                // the launcher can exit before its usage is first measured
+                let started = SystemTime::now();
+                match bramlett::sandbox::spawn(
+                    launch
+                        .command()
+                        .stdout(Stdio::piped())
+                        .stderr(Stdio::piped()),
+                    launch.creation_flags(),
+                    job.as_ref(),
+                ) {
+                    Ok(mut child) => {
+                        let job = job.map(Arc::new);
+                        let tracking = child.id().map(|pid| {
+                            let usage = bramlett::usage::track(
+                                ctx.usage.clone(),
+                                game.info.id,
+                                pid,
+                                started,
+                                job,
+                            );
+                            tokio::spawn(usage)
+                        });
+                        let stdout = child.stdout.take();
+                        let stderr = child.stderr.take();
+                        let capture = async {
+                            if let Some(stdout) = stdout {
+                                output.capture(stdout).await;
+                            }
+                        };
+                        let capture_err = async {
+                            if let Some(stderr) = stderr {
+                                output.capture(stderr).await;
+                            }
+                        };
+                        let (status, (), ()) = tokio::join!(child.wait(), capture, capture_err);
+                        let code = status.ok().and_then(|s| s.code());
+                        if let Some(code) = code.filter(|&code| code != 0) {
+                            let message = format!("exited with code {code}");
+                            ctx.config
+                                .record_error(game.info.id, ErrorStage::Run, message);
+                        }
+                        // a launcher can exit as soon as it's started the real game, so the game's
+                        // only stopped once everything it started has too
+                        if let Some(tracking) = tracking {
+                            let _ = tracking.await;
+                        }
+                        output.exited(code);
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to start {exe:?}: {e}");
+                        // it can be quarantined between being checked and started
+                        let message = if bramlett::antivirus::is_blocked(&e) || !exe.is_file() {
+                            ctx.quarantined.insert(game.info.id);
+                            bramlett::ClientError::ExeQuarantined(exe.clone()).to_string()
+                        } else {
+                            format!("couldn't start {}: {e}", exe.display())
+                        };
+                        ctx.config
+                            .record_error(game.info.id, ErrorStage::Run, message.clone());
+                        output.push(message);
+                        output.exited(None);
+                    }
+                }
+            })
+            .await;
 
             tracing::info!("game stopped: {game:?}");
+            ctx.save_sizes.remove(&game.info.id);
+            if !game.info.cleanup_globs.is_empty() {
+                let config = ctx.config.clone();
+                let info = game.info.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    bramlett::cleanup::clean_up_after_run(&config, &info);
+                })
+                .await;
+            }
 
             let mut game = games.get_mut(&game.info.id).unwrap();
-            game.status = GameStatus::Ready;
+            ctx.config.set_status(&mut game, GameStatus::Ready);
         });
         Ok(Void)
     }
 
+    /// Runs the latest version of an installed game's `post_install` hook again, without
+    /// reinstalling it. Unless `force` is set, nothing is done if the hook has already run from
+    /// the same hooks. Returns whether it's being run.
+    pub fn rerun_setup(ctx: &Ctx, game: GameId, force: Option<bool>) -> FieldResult<bool> {
+        let force = force.unwrap_or(false);
+        let games = ctx.config.games();
+        {
+            let mut game = games.get_mut(&game).ok_or(GraphQLError::NotFound)?;
+            if !matches!(game.status, GameStatus::Ready) {
+                return Err(GraphQLError::NotInstalled.into());
+            }
+            game.hooks_error = bramlett::py::check_hooks(&ctx.config, &game.info);
+            if game.hooks_error.is_some() {
+                return Err(GraphQLError::BrokenHooks.into());
+            }
+            if !force && bramlett::py::setup_is_up_to_date(&ctx.config, &game.info) {
+                return Ok(false);
+            }
+            // keeps the game from being run or deleted while the hook runs
+            ctx.config
+                .set_status(&mut game, GameStatus::Installing(watch::channel((0, 0)).1));
+        }
+
+        tracing::info!("re-running post_install for {game}");
+        // a slow hook shows as running instead of holding up the request, and a failed one is
+        // shown on the game
+        tokio::spawn({
+            let ctx = ctx.clone();
+            async move {
+                let _ = bramlett::py::run_post_install(&ctx, game, true, true).await;
+                if let Some(mut game) = games.get_mut(&game) {
+                    ctx.config.set_status(&mut game, GameStatus::Ready);
+                }
+            }
+        });
+        Ok(true)
+    }
+
+    /// Deletes an installed game's files and installs it again from a fresh download, running its
+    /// `post_install` hook again. Its saves are kept.
+    pub async fn reinstall(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
+        let games = ctx.config.games();
+        let dir = ctx.config.game_dir(game);
+        {
+            let mut game = games.get_mut(&game).ok_or(GraphQLError::NotFound)?;
+            if !matches!(game.status, GameStatus::Ready) {
+                return Err(GraphQLError::NotInstalled.into());
+            }
+            if ctx.config.save_dir(game.info.id).starts_with(&dir) {
+                return Err(GraphQLError::SavesInGameDir.into());
+            }
+            // keeps the game from being run or installed while its files are deleted
+            ctx.config
+                .set_status(&mut game, GameStatus::Installing(watch::channel((0, 0)).1));
+        }
+
+        tracing::info!("reinstalling game {game}; deleting {dir:?}");
+        let removed = bramlett::cleanup::remove_dir_all_retrying(&dir).await;
+        if let Some(mut game) = games.get_mut(&game) {
+            let status = if removed.is_ok() || !dir.exists() {
+                GameStatus::NotDownloaded
+            } else {
+                GameStatus::Ready
+            };
+            ctx.config.set_status(&mut game, status);
+        }
+        removed?;
+        // the archive might be what's broken
+        ctx.config.uncache_archive(game);
+
+        let (game, tx) = start_install(ctx, game)?;
+        tokio::spawn({
+            let ctx = ctx.clone();
+            async move {
+                let name = game.info.name.clone();
+                if let Err(e) = bramlett::download::install_game_and_set_up(&ctx, game, tx).await {
+                    tracing::error!("failed to reinstall {name}: {e:#}");
+                }
+            }
+        });
+        Ok(Void)
+    }
+
+    /// Checks every installed game's files in the background, see `libraryAudit`.
+    pub fn audit_library(ctx: &Ctx) -> VoidEnum {
+        let ctx = ctx.clone();
+        tokio::spawn(async move { bramlett::manifest::audit_library(&ctx).await });
+        Void
+    }
+
+    /// Reinstalls each of `games`, like after an audit found them damaged. Returns how many are
+    /// being reinstalled; the ones that can't be are skipped.
+    pub async fn repair(ctx: &Ctx, games: Vec<GameId>) -> FieldResult<i32> {
+        let mut repairing = 0;
+        let mut error = None;
+        for game in games {
+            match Self::reinstall(ctx, game).await {
+                Ok(Void) => {
+                    ctx.library_audit.lock().unwrap().audits.remove(&game);
+                    repairing += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("can't repair game {game}: {}", e.message());
+                    error = Some(e);
+                }
+            }
+        }
+        match error {
+            Some(e) if repairing == 0 => Err(e),
+            _ => Ok(repairing),
+        }
+    }
+
+    /// Installs a game in `path` instead of the games folder, or back in the games folder if
+    /// it's `null`. An installed game is moved there.
+    pub async fn set_install_dir(
+        ctx: &Ctx,
+        game: GameId,
+        path: Option<String>,
+    ) -> FieldResult<VoidEnum> {
+        let path = path.filter(|p| !p.trim().is_empty()).map(PathBuf::from);
+        if let Some(path) = &path {
+            bramlett::check_writable(path)?;
+        }
+        let games = ctx.config.games();
+        let installed = {
+            let mut game = games.get_mut(&game).ok_or(GraphQLError::NotFound)?;
+            match game.status {
+                GameStatus::Ready => {
+                    // keeps the game from being run or deleted while it's moved
+                    ctx.config
+                        .set_status(&mut game, GameStatus::Installing(watch::channel((0, 0)).1));
+                    true
+                }
+                GameStatus::NotDownloaded | GameStatus::Failed(_) => false,
+                _ => return Err(GraphQLError::NotMoved.into()),
+            }
+        };
+
+        let old = ctx.config.install_dir(game);
+        let from = ctx.config.game_dir(game);
+        ctx.config.set_install_dir(game, path);
+        let to = ctx.config.game_dir(game);
+        tracing::info!("moving {game} from {from:?} to {to:?}");
+        let res = if installed && from != to {
+            tokio::task::spawn_blocking(move || bramlett::cleanup::move_dir(&from, &to))
+                .await
+                .expect("moving task panicked")
+        } else {
+            Ok(())
+        };
+
+        if res.is_err() {
+            // the game is still where it was
+            ctx.config.set_install_dir(game, old);
+        }
+        if installed {
+            if let Some(mut game) = games.get_mut(&game) {
+                ctx.config.set_status(&mut game, GameStatus::Ready);
+            }
+        }
+        res?;
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Opens a game's save folder in the file manager, creating it if the game hasn't yet.
+    pub fn open_saves_folder(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
+        if !ctx.config.games().contains_key(&game) {
+            return Err(GraphQLError::NotFound.into());
+        }
+        let dir = ctx.config.save_dir(game);
+        std::fs::create_dir_all(&dir)?;
+        bramlett::open_folder(&dir)?;
+        Ok(Void)
+    }
+
+    /// Opens Windows Security's virus & threat protection settings, where the games folder can be
+    /// excluded so games aren't quarantined.
+    pub fn open_antivirus_exclusions() -> FieldResult<VoidEnum> {
+        bramlett::antivirus::open_exclusions()?;
+        Ok(Void)
+    }
+
+    /// Opens a game's homepage in the browser.
+    pub fn open_homepage(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
+        let homepage = ctx
+            .config
+            .games()
+            .get(&game)
+            .ok_or(GraphQLError::NotFound)?
+            .info
+            .homepage
+            .clone()
+            .ok_or(GraphQLError::NotFound)?;
+        bramlett::open_web_page(&homepage)?;
+        Ok(Void)
+    }
+
+    /// Forgets the errors a game has had.
+    pub fn clear_errors(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
+        if !ctx.config.games().contains_key(&game) {
+            return Err(GraphQLError::NotFound.into());
+        }
+        ctx.config.clear_error_history(game);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Keeps a game on the version it's on, or lets it be updated with the game list again.
+    pub fn set_pinned(ctx: &Ctx, game: GameId, pinned: bool) -> FieldResult<VoidEnum> {
+        if !ctx.config.games().contains_key(&game) {
+            return Err(GraphQLError::NotFound.into());
+        }
+        ctx.config.set_pinned(game, pinned);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Updates a pinned game to the server's version, keeping it pinned. Returns whether there
+    /// was an update.
+    pub fn apply_update(ctx: &Ctx, game: GameId) -> FieldResult<bool> {
+        if !ctx.config.games().contains_key(&game) {
+            return Err(GraphQLError::NotFound.into());
+        }
+        let updated = ctx.config.apply_held_update(game);
+        ctx.config.save_now()?;
+        Ok(updated)
+    }
+
+    /// Moves a game to `position` in the install queue, counting from 0, so its download starts
+    /// before or after the others. Returns whether it was in the queue.
+    pub fn move_in_install_queue(ctx: &Ctx, game: GameId, position: i32) -> FieldResult<bool> {
+        // negative positions are the front
+        let moved = ctx.move_in_install_queue(game, usize::try_from(position).unwrap_or(0));
+        ctx.config.save_now()?;
+        Ok(moved)
+    }
+
+    /// Takes a game out of the install queue, canceling its install. Returns whether it was in
+    /// the queue.
+    pub fn remove_from_install_queue(ctx: &Ctx, game: GameId) -> FieldResult<bool> {
+        tracing::info!("removing {game} from the install queue");
+        let removed = ctx.remove_from_install_queue(game);
+        ctx.config.save_now()?;
+        Ok(removed)
+    }
+
     pub async fn delete(ctx: &Ctx, game: GameId) -> FieldResult<VoidEnum> {
         let games = ctx.config.games();
 
@@ -243,18 +1213,193 @@ impl Mutation {
             tracing::info!("deleting game: {game:?}");
 
             tokio::fs::remove_dir_all(ctx.config.game_dir(game.info.id)).await?;
-            game.status = GameStatus::NotDownloaded;
+            ctx.config.set_status(&mut game, GameStatus::NotDownloaded);
+            let exe = ctx.config.launch(&game.info).exe;
+            if let Err(e) = bramlett::compat::clear(&exe) {
+                tracing::warn!(
+                    "failed to clear {}'s compatibility settings: {e}",
+                    game.info.name
+                );
+            }
+            ctx.quarantined.remove(&game.info.id);
         }
+        ctx.config.save();
 
         Ok(Void)
     }
 
-    pub async fn update_game_list(ctx: &Ctx) -> FieldResult<VoidEnum> {
-        let ctx = ctx.clone();
-        bramlett::update_game_list(&ctx.config, true).await?;
+    /// Pauses every active download.
+    pub fn pause_downloads(ctx: &Ctx) -> VoidEnum {
+        tracing::info!("pausing downloads");
+        ctx.pause.set(true);
+        Void
+    }
+
+    /// Resumes every paused download.
+    pub fn resume_downloads(ctx: &Ctx) -> VoidEnum {
+        tracing::info!("resuming downloads");
+        ctx.pause.set(false);
+        Void
+    }
+
+    /// Moves the games directory to `path`. Games that aren't installed there are marked as not
+    /// downloaded.
+    pub fn set_games_dir(ctx: &Ctx, path: String) -> FieldResult<VoidEnum> {
+        let path = PathBuf::from(path);
+        bramlett::check_writable(&path)?;
+
+        tracing::info!("setting games dir to {path:?}");
+        ctx.config.set_games_dir(path);
+        for mut game in ctx.config.games().iter_mut() {
+            if matches!(game.status, GameStatus::Ready)
+                && !ctx.config.game_dir(game.info.id).exists()
+            {
+                ctx.config.set_status(&mut game, GameStatus::NotDownloaded);
+            }
+        }
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Moves the saves directory to `path`. Existing saves aren't moved.
+    pub fn set_saves_dir(ctx: &Ctx, path: String) -> FieldResult<VoidEnum> {
+        let path = PathBuf::from(path);
+        std::fs::create_dir_all(&path)?;
+
+        tracing::info!("setting saves dir to {path:?}");
+        ctx.config.set_saves_dir(path);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Downloads archives to `path` instead of the games folder, or to the games folder again if
+    /// it's `null`. Archives that were already downloaded aren't moved.
+    pub fn set_staging_dir(ctx: &Ctx, path: Option<String>) -> FieldResult<VoidEnum> {
+        let path = path.filter(|p| !p.trim().is_empty()).map(PathBuf::from);
+        if let Some(path) = &path {
+            bramlett::check_writable(path)?;
+        }
+
+        tracing::info!("setting staging dir to {path:?}");
+        ctx.config.set_staging_dir(path);
+        ctx.config.save_now()?;
         Ok(Void)
     }
 
+    /// Sets how many times a download that fails partway is retried, from 0 to 10.
+    pub fn set_download_retries(ctx: &Ctx, retries: i32) -> FieldResult<VoidEnum> {
+        // negative counts become too big, so they're out of range too
+        ctx.config
+            .set_download_retries(u32::try_from(retries).unwrap_or(u32::MAX))?;
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Sets how many downloads and extractions can run at once, from 1 to 16. Waiting ones start
+    /// right away if there's now room for them.
+    pub fn set_max_concurrent_work(ctx: &Ctx, limit: i32) -> FieldResult<VoidEnum> {
+        ctx.set_max_concurrent_work(usize::try_from(limit).unwrap_or(0))?;
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Sets how many hosts are asked for a game's size at once before installing several games,
+    /// from 1 to 16.
+    pub fn set_preflight_concurrency(ctx: &Ctx, limit: i32) -> FieldResult<VoidEnum> {
+        ctx.config
+            .set_preflight_concurrency(usize::try_from(limit).unwrap_or(0))?;
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Keeps downloaded archives after installing them, so reinstalling a game doesn't download
+    /// it again.
+    pub fn set_keep_archives(ctx: &Ctx, keep_archives: bool) -> FieldResult<VoidEnum> {
+        ctx.config.set_keep_archives(keep_archives);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Limits how many bytes of archives are kept, or removes the limit if it's `null`. The least
+    /// recently used archives are deleted to get under it.
+    pub fn set_archive_cache_limit(ctx: &Ctx, bytes: Option<f64>) -> FieldResult<VoidEnum> {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        ctx.config
+            .set_archive_cache_limit(bytes.map(|bytes| bytes.max(0.0) as u64));
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Deletes the kept archives, returning how many bytes were freed.
+    pub fn clear_archive_cache(ctx: &Ctx) -> FieldResult<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        Ok(ctx.config.clear_archive_cache()? as f64)
+    }
+
+    /// Removes leftover archives, partial downloads and failed installs from the games directory,
+    /// returning how many bytes were freed.
+    pub fn clean_up(ctx: &Ctx) -> FieldResult<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        Ok(bramlett::cleanup::remove_orphans(&ctx.config)? as f64)
+    }
+
+    /// Sets the HTTP or SOCKS5 proxy to download through. `null` goes back to the `HTTPS_PROXY`
+    /// or `HTTP_PROXY` environment variable, if there is one, except for hosts in `NO_PROXY`.
+    pub fn set_proxy(ctx: &Ctx, proxy: Option<String>) -> FieldResult<VoidEnum> {
+        let proxy = proxy.filter(|p| !p.trim().is_empty());
+        tracing::info!(
+            "setting proxy to {:?}",
+            proxy.as_deref().map(bramlett::proxy_without_credentials)
+        );
+        ctx.config.set_proxy(proxy)?;
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Marks the first run wizard as finished so it isn't shown again.
+    pub fn complete_setup(ctx: &Ctx) -> FieldResult<VoidEnum> {
+        ctx.config.set_setup_complete(true);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Overrides the UI language. `null` goes back to the system language.
+    pub fn set_locale(ctx: &Ctx, locale: Option<String>) -> FieldResult<VoidEnum> {
+        ctx.config.set_locale(locale);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Switches between showing the games as a list or a grid.
+    pub fn set_list_view(ctx: &Ctx, list_view: bool) -> FieldResult<VoidEnum> {
+        ctx.config.set_list_view(list_view);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Checks that a game's files are there before running it, marking it as failed so it can be
+    /// reinstalled if they aren't.
+    pub fn set_verify_before_run(ctx: &Ctx, verify_before_run: bool) -> FieldResult<VoidEnum> {
+        ctx.config.set_verify_before_run(verify_before_run);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Shows or hides details most people don't need, like how games are launched.
+    pub fn set_advanced(ctx: &Ctx, advanced: bool) -> FieldResult<VoidEnum> {
+        ctx.config.set_advanced(advanced);
+        ctx.config.save_now()?;
+        Ok(Void)
+    }
+
+    /// Fetches the game list now instead of waiting for the next refresh, returning how many
+    /// games were added.
+    pub async fn update_game_list(ctx: &Ctx) -> FieldResult<i32> {
+        let ctx = ctx.clone();
+        let added = bramlett::update_game_list(&ctx.config, true).await?;
+        Ok(i32::try_from(added).unwrap_or(i32::MAX))
+    }
+
     pub async fn launch_firefox() -> FieldResult<FirefoxStatus> {
         bramlett::firefox::launch(bramlett::firefox::get_profile_path().is_none()).await?;
 
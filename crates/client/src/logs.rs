@@ -0,0 +1,172 @@
+//! Keeps the most recent log lines in memory so they can be included in bug reports, and the
+//! output of games so users can see why one closed.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io;
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::Config;
+
+/// How many log lines are kept.
+pub const CAPACITY: usize = 1000;
+
+/// A ring buffer of log lines, usable as a `tracing_subscriber` writer.
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer(Arc<Mutex<Lines>>);
+
+#[derive(Debug, Default)]
+struct Lines {
+    lines: VecDeque<String>,
+    /// Whether the last line hasn't been ended yet.
+    partial: bool,
+}
+
+impl LogBuffer {
+    /// The buffer the app logs to.
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<LogBuffer> = OnceLock::new();
+        GLOBAL.get_or_init(Self::default)
+    }
+
+    /// The kept log lines, oldest first.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().lines.iter().cloned().collect()
+    }
+}
+
+impl io::Write for LogBuffer {
+    #[allow(clippy::missing_panics_doc)]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        let mut buffer = self.0.lock().unwrap();
+        for (i, line) in text.split_inclusive('\n').enumerate() {
+            let ended = line.ends_with('\n');
+            let line = line.trim_end_matches(['\r', '\n']);
+            let partial = buffer.partial;
+            match buffer.lines.back_mut() {
+                // a write can stop in the middle of a line
+                Some(last) if i == 0 && partial => last.push_str(line),
+                _ => buffer.lines.push_back(line.to_owned()),
+            }
+            buffer.partial = !ended;
+        }
+        let excess = buffer.lines.len().saturating_sub(CAPACITY);
+        buffer.lines.drain(..excess);
+        drop(buffer);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for LogBuffer {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// How many lines of a game's output are kept.
+pub const GAME_OUTPUT_CAPACITY: usize = 500;
+
+/// What a game printed to stdout and stderr the last time it was run, and how it exited.
+#[derive(Debug, Clone, Default)]
+pub struct GameOutput(Arc<Mutex<Output>>);
+
+#[derive(Debug, Default)]
+struct Output {
+    lines: VecDeque<String>,
+    exited: bool,
+    exit_code: Option<i32>,
+}
+
+impl GameOutput {
+    /// Adds a line, dropping the oldest one if there are too many.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn push(&self, line: String) {
+        let mut output = self.0.lock().unwrap();
+        if output.lines.len() == GAME_OUTPUT_CAPACITY {
+            output.lines.pop_front();
+        }
+        output.lines.push_back(line);
+    }
+
+    /// Adds every line read from `reader` until it's closed.
+    pub async fn capture(&self, reader: impl AsyncRead + Unpin) {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+        // games don't always print UTF-8, so the lines are read as bytes
+        while matches!(reader.read_until(b'\n', &mut line).await, Ok(n) if n > 0) {
+            let text = String::from_utf8_lossy(&line);
+            self.push(text.trim_end_matches(['\r', '\n']).to_owned());
+            line.clear();
+        }
+    }
+
+    /// Records that the game exited, with its exit code if it had one.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn exited(&self, code: Option<i32>) {
+        let mut output = self.0.lock().unwrap();
+        output.exited = true;
+        output.exit_code = code;
+    }
+
+    /// The kept lines, oldest first.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().lines.iter().cloned().collect()
+    }
+
+    /// Whether the game has exited.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn has_exited(&self) -> bool {
+        self.0.lock().unwrap().exited
+    }
+
+    /// The game's exit code, if it has exited with one. Games killed by a signal don't have one.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn exit_code(&self) -> Option<i32> {
+        self.0.lock().unwrap().exit_code
+    }
+}
+
+/// Puts together a plain text bug report for the user to send.
+///
+/// It has the app version, the error (if there is one), the config and the recent logs. The
+/// user's home directory is replaced with `~` everywhere, the proxy is hidden since it might have
+/// a password in it, and the game list is left out since it's the same for everyone.
+pub fn bug_report(config: &Config, error: Option<&str>) -> String {
+    let mut config = serde_json::to_value(config).unwrap_or_default();
+    if let Some(config) = config.as_object_mut() {
+        config.remove("games");
+        if config.get("proxy").is_some_and(|p| !p.is_null()) {
+            config.insert("proxy".into(), "<hidden>".into());
+        }
+    }
+    let config = serde_json::to_string_pretty(&config).unwrap_or_default();
+
+    // writing to a `String` can't fail
+    let mut report = format!(
+        "Bramletts Games v{} on {}\n\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS
+    );
+    if let Some(error) = error {
+        let _ = write!(report, "Error:\n{error}\n\n");
+    }
+    let _ = write!(report, "Config:\n{config}\n\nLogs:\n");
+    for line in LogBuffer::global().lines() {
+        let _ = writeln!(report, "{line}");
+    }
+
+    match dirs::home_dir() {
+        Some(home) => report.replace(&*home.to_string_lossy(), "~"),
+        None => report,
+    }
+}
@@ -2,15 +2,20 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use bramlett::{update_game_list, Config, Ctx};
-use std::{sync::Arc, time::Duration};
+use std::{
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
 use tracing_subscriber::{filter::filter_fn, layer::SubscriberExt, util::SubscriberInitExt, Layer};
 use warp::Filter;
 
-use crate::socks5::AuthMethods;
-
 mod gql;
 mod socks5;
+#[cfg(feature = "tray")]
+mod tray;
 
 const WEB_PORT: u16 = 8635;
 const SOCKS_PORT: u16 = 8636;
@@ -28,6 +33,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 )
                 .with_filter(filter_fn(|m| !m.target().contains("graphql"))),
         )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(bramlett::logs::LogBuffer::global().clone())
+                .with_filter(tracing::level_filters::LevelFilter::DEBUG)
+                .with_filter(filter_fn(|m| !m.target().contains("graphql"))),
+        )
         .init();
 
     #[cfg(not(debug_assertions))]
@@ -69,27 +81,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Err(e) => {
                 tracing::warn!("failed to parse config file: {e:#}");
                 let config = Config::default();
-                config.save()?;
+                config.save_now()?;
                 config
             }
         }
     } else {
         let config = Config::default();
-        config.save()?;
+        config.save_now()?;
         config
     };
 
     tracing::info!("save dir: {:#?}", config.saves_dir());
     tracing::info!("games dir: {:#?}", config.games_dir());
 
-    #[allow(unused_variables)]
     let (py_tx, py_rx) = mpsc::unbounded_channel();
 
-    let ctx = Ctx {
-        config: config.clone(),
-        client: reqwest::Client::new(),
-        py_tx,
-    };
+    let ctx = Ctx::new(config.clone(), config.http_client()?, py_tx);
 
     let schema = Arc::new(gql::schema());
 
@@ -149,31 +156,69 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .enable_all()
         .build()?; // the below future doesn't run if this isn't put in it's own variable. maybe a lifetime issue?
 
-    #[allow(unused_variables)]
+    // the proxy games are downloaded through is the network's way out, so Firefox goes through
+    // it too
+    let upstream = config
+        .proxy()
+        .and_then(|proxy| match socks5::Upstream::parse(&proxy) {
+            Ok(upstream) => Some(upstream),
+            Err(e) => {
+                tracing::warn!("not using the proxy for Firefox: {e}");
+                None
+            }
+        });
     let socks_fut = rt.spawn(async move {
-        socks5::Merino::new(
-            SOCKS_PORT,
-            "127.0.0.1",
-            vec![AuthMethods::NoAuth as u8],
-            vec![],
-            Duration::from_secs(60),
-        )
-        .await
-        .unwrap()
-        .serve()
-        .await;
+        let socks = socks5::MerinoConfig::new(&[
+            SocketAddr::from((Ipv4Addr::LOCALHOST, SOCKS_PORT)),
+            SocketAddr::from((Ipv6Addr::LOCALHOST, SOCKS_PORT)),
+        ]);
+        let bound = match upstream {
+            Some(upstream) => match socks.clone().with_upstream(upstream).bind().await {
+                Ok(bound) => Ok(bound),
+                Err(e) => {
+                    tracing::error!("{e} -- connecting directly instead");
+                    socks.bind().await
+                }
+            },
+            None => socks.bind().await,
+        };
+        bound
+            .unwrap()
+            .with_idle_timeout(Duration::from_secs(10 * 60))
+            .spawn()
     });
 
-    // std::thread::spawn(move || py_loop(py_rx, ctx)); // ugly panic rn
+    rt.spawn(bramlett::log_status_events(config.status_events()));
+    rt.spawn(config.clone().run_saver(bramlett::SAVE_INTERVAL));
+    if let Some(dir) = config.dev_hooks_dir() {
+        tracing::info!("developer mode: loading hooks from {dir:?}");
+        rt.spawn(bramlett::py::watch_dev_hooks(config.clone()));
+    }
+    // saves whatever the saver hasn't gotten to yet when the app is closed
+    let exiting_config = config.clone();
+
+    let (responses_tx, responses_rx) = mpsc::unbounded_channel();
+    rt.spawn(bramlett::py::handle_responses(ctx.clone(), responses_rx));
+    std::thread::spawn({
+        let ctx = ctx.clone();
+        move || bramlett::py::py_loop(py_rx, responses_tx, ctx)
+    });
+
+    #[cfg(feature = "tray")]
+    let tray_ctx = ctx.clone();
 
     #[allow(unused_variables)]
     let server_fut = rt.spawn(async move {
         if let Err(e) = update_game_list(&config, true).await {
             tracing::warn!("failed to update game list: {e:#} -- is the server running?");
-        } else {
-            config.save().unwrap();
         };
         tracing::info!("{} games", config.games().len());
+        tokio::spawn(bramlett::refresh_game_list(
+            config.clone(),
+            bramlett::REFRESH_INTERVAL,
+        ));
+
+        bramlett::download::resume_downloads(&ctx);
 
         let _ = tx.send(());
 
@@ -193,6 +238,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         rx.blocking_recv()?;
 
+        #[cfg(feature = "tray")]
+        let event_loop = wry::application::event_loop::EventLoopBuilder::with_user_event().build();
+        #[cfg(not(feature = "tray"))]
         let event_loop = EventLoop::new();
         let window = WindowBuilder::new()
             .with_title("Bramlett's Games")
@@ -202,19 +250,74 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         } else {
             web_port
         };
-        let _webview = WebViewBuilder::new(window)?
+        let webview = WebViewBuilder::new(window)?
             .with_url(&format!("http://localhost:{port}"))?
             .build()?;
 
+        // without a tray icon, closing the window is the only way to quit, so it still quits
+        #[cfg(feature = "tray")]
+        let tray = match tray::Tray::new() {
+            Ok(tray) => {
+                tray::forward_events(&event_loop.create_proxy());
+                Some(tray)
+            }
+            Err(e) => {
+                tracing::warn!("failed to create the tray icon: {e}");
+                None
+            }
+        };
+
+        let rt_handle = rt.handle().clone();
+        let mut socks_fut = Some(socks_fut);
         event_loop.run(move |event, _, control_flow| {
             *control_flow = ControlFlow::Wait;
+            let mut quit = |control_flow: &mut ControlFlow| {
+                if !confirm_quit(&exiting_config) {
+                    return;
+                }
+                if let Err(e) = exiting_config.flush() {
+                    tracing::warn!("failed to save config: {e:#}");
+                }
+                if let Some(socks) = socks_fut.take() {
+                    stop_socks(&rt_handle, socks);
+                }
+                *control_flow = ControlFlow::Exit;
+            };
+
+            #[cfg(feature = "tray")]
+            if let Some(tray) = &tray {
+                // wakes up now and then to keep the tooltip's progress current
+                *control_flow =
+                    ControlFlow::WaitUntil(std::time::Instant::now() + tray::REFRESH_INTERVAL);
+                tray.refresh(&tray_ctx);
+            }
 
             match event {
                 Event::NewEvents(StartCause::Init) => tracing::info!("wry has started!"),
                 Event::WindowEvent {
                     event: WindowEvent::CloseRequested,
                     ..
-                } => *control_flow = ControlFlow::Exit,
+                } => {
+                    // keeps running in the tray
+                    #[cfg(feature = "tray")]
+                    if tray.is_some() {
+                        webview.window().set_visible(false);
+                        return;
+                    }
+                    quit(control_flow);
+                }
+                #[cfg(feature = "tray")]
+                Event::UserEvent(e) => {
+                    let action = tray.as_ref().and_then(|tray| tray.handle(&tray_ctx, &e));
+                    match action {
+                        Some(tray::Action::Show) => {
+                            webview.window().set_visible(true);
+                            webview.window().set_focus();
+                        }
+                        Some(tray::Action::Quit) => quit(control_flow),
+                        None => {}
+                    }
+                }
                 _ => (),
             }
         })
@@ -222,12 +325,56 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(not(feature = "webview"))]
     {
         rt.block_on(async {
-            server_fut.await.unwrap();
+            tokio::select! {
+                res = server_fut => res.unwrap(),
+                _ = tokio::signal::ctrl_c() => tracing::info!("shutting down"),
+            }
         });
+        stop_socks(rt.handle(), socks_fut);
+        exiting_config.flush()?;
         Ok(())
     }
 }
 
+/// Stops the SOCKS server, waiting until its port is free so the app can be started again right
+/// away. If it's still starting, it's stopped before it finishes.
+fn stop_socks(rt: &tokio::runtime::Handle, socks: JoinHandle<socks5::ShutdownHandle>) {
+    socks.abort();
+    rt.block_on(async {
+        match socks.await {
+            Ok(server) => server.shutdown().await,
+            Err(e) if e.is_cancelled() => {}
+            Err(e) => tracing::error!("the SOCKS server failed to start: {e}"),
+        }
+    });
+}
+
+/// Asks whether to quit while games are downloading or installing. They're resumed the next time
+/// the app starts, but anything downloaded since the last checkpoint is downloaded again.
+#[cfg(feature = "webview")]
+fn confirm_quit(config: &Config) -> bool {
+    use dialog::DialogBox;
+
+    let activity = config.activity();
+    if activity.downloading == 0 && activity.installing == 0 {
+        return true;
+    }
+    let question = format!(
+        "Games are still being installed ({activity}). Quit anyway? They'll carry on from where \
+         they left off the next time you open the app."
+    );
+    match dialog::Question::new(question)
+        .title("Downloads in progress")
+        .show()
+    {
+        Ok(choice) => choice == dialog::Choice::Yes,
+        Err(e) => {
+            tracing::warn!("couldn't ask whether to quit, so quitting: {e}");
+            true
+        }
+    }
+}
+
 #[cfg(not(debug_assertions))]
 mod frontend {
     use rust_embed::RustEmbed;
@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use common::GameId;
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+
+use crate::{ClientError, Ctx, GameStatus, Result};
+
+/// How many games [`audit_library`] checks at once. Each one reads its game's files, so checking
+/// more would only make the disk jump between them.
+pub const AUDIT_CONCURRENCY: usize = 2;
+
+/// The name of the manifest file, stored in the root of each game's directory.
+pub const MANIFEST_FILE: &str = ".bramlett-manifest.json";
+
+/// A list of the files extracted into a game's directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    /// Files keyed by their path relative to the game directory, with `/` separators.
+    pub files: BTreeMap<String, ManifestEntry>,
+    /// The [hash of the hooks](crate::py::hooks_hash) the game's `post_install` hook last ran
+    /// from, so it isn't run again if they haven't changed.
+    #[serde(default, with = "hex_hash", skip_serializing_if = "Option::is_none")]
+    pub post_install_hooks: Option<[u8; 32]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ManifestEntry {
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The CRC32 of the file, as recorded in the archive. `None` if the archive didn't have one.
+    pub crc: Option<u64>,
+    /// The SHA-256 of the file as it was extracted, if
+    /// [`Config::hash_files`](crate::Config::hash_files) was set.
+    #[serde(default, with = "hex_hash", skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<[u8; 32]>,
+}
+
+impl Manifest {
+    pub fn path(game_dir: &Path) -> PathBuf {
+        game_dir.join(MANIFEST_FILE)
+    }
+
+    /// Loads the manifest of a game directory, if there is a valid one.
+    pub fn load(game_dir: &Path) -> Option<Self> {
+        let file = std::fs::File::open(Self::path(game_dir)).ok()?;
+        match serde_json::from_reader(std::io::BufReader::new(file)) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                tracing::warn!("ignoring invalid manifest in {game_dir:?}: {e:#}");
+                None
+            }
+        }
+    }
+
+    /// Writes the manifest into a game directory.
+    ///
+    /// # Errors
+    /// Returns an error if the manifest can't be written.
+    pub fn save(&self, game_dir: &Path) -> Result<()> {
+        crate::write_atomically(&Self::path(game_dir), |file| {
+            serde_json::to_writer(file, self)?;
+            Ok(())
+        })
+    }
+
+    /// Whether the installed file at `key` is known to match `entry`, so it doesn't need to be
+    /// extracted again. Only the size and CRC are compared, since those are what the archive has.
+    pub fn is_unchanged(&self, game_dir: &Path, key: &str, entry: ManifestEntry) -> bool {
+        entry.crc.is_some()
+            && self
+                .files
+                .get(key)
+                .is_some_and(|e| e.size == entry.size && e.crc == entry.crc)
+            && std::fs::metadata(game_dir.join(key)).is_ok_and(|m| m.len() == entry.size)
+    }
+}
+
+/// Stores hashes as hex strings, which are shorter and easier to compare by hand than arrays of
+/// numbers.
+mod hex_hash {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::fmt::Write;
+
+    #[allow(clippy::ref_option)]
+    pub fn serialize<S: Serializer>(hash: &Option<[u8; 32]>, s: S) -> Result<S::Ok, S::Error> {
+        let Some(hash) = hash else {
+            return s.serialize_none();
+        };
+        let hex = hash.iter().fold(String::with_capacity(64), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        });
+        s.serialize_some(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<[u8; 32]>, D::Error> {
+        let Some(hex) = Option::<String>::deserialize(d)? else {
+            return Ok(None);
+        };
+        if hex.len() != 64 {
+            return Err(D::Error::custom("a SHA-256 hash has 64 hex digits"));
+        }
+        let mut hash = [0; 32];
+        for (byte, digits) in hash.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = std::str::from_utf8(digits)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| D::Error::custom(format!("invalid hash {hex:?}")))?;
+        }
+        Ok(Some(hash))
+    }
+}
+
+/// Quickly checks that an installed game isn't obviously broken, like after an antivirus deleted
+/// its executable.
+///
+/// The executable has to be there, and so does every file in the game's [`Manifest`], if it has
+/// one. Only file metadata is read, so this is fast enough to do before every launch. Files
+/// aren't compared to the sizes in the manifest, since the game's hooks may have changed them.
+///
+/// # Errors
+/// Returns [`ClientError::BrokenInstall`] naming the first missing file.
+pub fn verify_install(game_dir: &Path, exe: &str) -> Result<()> {
+    let missing = |name: &str| ClientError::BrokenInstall(format!("{name} is missing"));
+    if !std::fs::metadata(game_dir.join(exe)).is_ok_and(|m| m.is_file() && m.len() > 0) {
+        return Err(missing(exe));
+    }
+    let Some(manifest) = Manifest::load(game_dir) else {
+        return Ok(());
+    };
+    manifest
+        .files
+        .keys()
+        .find(|key| !game_dir.join(key).is_file())
+        .map_or(Ok(()), |key| Err(missing(key)))
+}
+
+/// What [`audit_install`] found in an installed game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Audit {
+    /// The files that are missing or damaged, relative to the game directory.
+    pub damaged: Vec<String>,
+    /// The size of every file in the manifest. Repairing a game downloads all of it again, so
+    /// this is about how much that is (before compression).
+    pub size: u64,
+}
+
+impl Audit {
+    pub const fn needs_repair(&self) -> bool {
+        !self.damaged.is_empty()
+    }
+}
+
+/// Checks an installed game more thoroughly than [`verify_install`], listing every file that's
+/// missing instead of stopping at the first.
+///
+/// A file is also damaged if it's empty when it shouldn't be, or if its SHA-256 was recorded
+/// (see [`Config::hash_files`](crate::Config::hash_files)) and doesn't match. Other files aren't
+/// compared to the sizes in the manifest, since the game's hooks may have changed them.
+pub fn audit_install(game_dir: &Path, exe: &str) -> Audit {
+    let mut audit = Audit::default();
+    if !std::fs::metadata(game_dir.join(exe)).is_ok_and(|m| m.is_file() && m.len() > 0) {
+        audit.damaged.push(exe.to_owned());
+    }
+    let Some(manifest) = Manifest::load(game_dir) else {
+        return audit;
+    };
+    for (key, entry) in &manifest.files {
+        audit.size += entry.size;
+        // a missing executable is already listed, but one that's there is checked like the rest
+        if key == exe && audit.damaged.first().is_some_and(|d| d == exe) {
+            continue;
+        }
+        let path = game_dir.join(key);
+        let intact = match std::fs::metadata(&path) {
+            Ok(m) if !m.is_file() || (m.len() == 0 && entry.size > 0) => false,
+            Ok(_) => entry
+                .sha256
+                .is_none_or(|hash| hash_file(&path).ok() == Some(hash)),
+            Err(_) => false,
+        };
+        if !intact {
+            audit.damaged.push(key.clone());
+        }
+    }
+    audit
+}
+
+fn hash_file(path: &Path) -> std::io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// The progress and results of [`audit_library`].
+#[derive(Debug, Clone, Default)]
+pub struct LibraryAudit {
+    pub running: bool,
+    /// How many games are being checked.
+    pub total: usize,
+    /// The games checked so far.
+    pub audits: BTreeMap<GameId, Audit>,
+}
+
+/// Checks every installed game with [`audit_install`] in the background,
+/// [`AUDIT_CONCURRENCY`] at a time, keeping the results in [`Ctx::library_audit`]. Does nothing
+/// if an audit is already running.
+#[allow(clippy::missing_panics_doc)]
+pub async fn audit_library(ctx: &Ctx) {
+    let games = ctx
+        .config
+        .games()
+        .iter()
+        .filter(|game| matches!(game.status, GameStatus::Ready))
+        .map(|game| {
+            let id = game.info.id;
+            (id, ctx.config.game_dir(id), game.info.exe.clone())
+        })
+        .collect::<Vec<_>>();
+    {
+        let mut audit = ctx.library_audit.lock().unwrap();
+        if audit.running {
+            return;
+        }
+        *audit = LibraryAudit {
+            running: true,
+            total: games.len(),
+            audits: BTreeMap::new(),
+        };
+    }
+    tracing::info!("auditing {} games", games.len());
+
+    let mut audits = futures::stream::iter(games)
+        .map(|(id, dir, exe)| async move {
+            let audit = tokio::task::spawn_blocking(move || audit_install(&dir, &exe)).await;
+            (id, audit)
+        })
+        .buffer_unordered(AUDIT_CONCURRENCY);
+    while let Some((id, audit)) = audits.next().await {
+        match audit {
+            Ok(audit) => {
+                if audit.needs_repair() {
+                    tracing::warn!("game {id} has {} damaged files", audit.damaged.len());
+                }
+                ctx.library_audit.lock().unwrap().audits.insert(id, audit);
+            }
+            Err(e) => tracing::error!("failed to audit game {id}: {e}"),
+        }
+    }
+    ctx.library_audit.lock().unwrap().running = false;
+    tracing::info!("finished auditing");
+}
@@ -0,0 +1,128 @@
+//! Pinning the games server's public key, so a proxy that intercepts TLS (like a school's) can't
+//! hand out its own game list even when its certificate is trusted.
+//!
+//! It's off unless [`Config::server_pin`](crate::Config::server_pin) is set.
+
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{tls::TlsInfo, Response};
+use sha2::{Digest, Sha256};
+
+use crate::{ClientError, Result};
+
+const SEQUENCE: u8 = 0x30;
+/// The tag of a certificate's version, which is left out of version 1 certificates.
+const VERSION: u8 = 0xa0;
+
+/// The SHA-256 hash of a certificate's public key (its `SubjectPublicKeyInfo`).
+///
+/// It's written like `sha256//VFudV5ofgOlUjkFEMzmXD1FHewPiWbBJIAa6s+HFnsU=`, as
+/// `curl --pinnedpubkey` takes it. The `sha256//` can be left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pin([u8; 32]);
+
+impl Pin {
+    /// The pin of a DER encoded certificate, or `None` if it can't be read.
+    pub fn of(certificate: &[u8]) -> Option<Self> {
+        public_key_info(certificate).map(|key| Self(Sha256::digest(key).into()))
+    }
+}
+
+impl FromStr for Pin {
+    type Err = ClientError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let hash = s.strip_prefix("sha256//").unwrap_or(s);
+        STANDARD
+            .decode(hash)
+            .ok()
+            .and_then(|hash| hash.try_into().ok())
+            .map(Self)
+            .ok_or_else(|| ClientError::InvalidPin(s.to_owned()))
+    }
+}
+
+/// Reads pins separated by `;`, so a new key can be pinned before the server switches to it.
+///
+/// # Errors
+/// Returns [`ClientError::InvalidPin`] if one of them isn't a pin, or there aren't any.
+pub fn parse_pins(pins: &str) -> Result<Vec<Pin>> {
+    let parsed = pins
+        .split(';')
+        .filter(|pin| !pin.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<_>>>()?;
+    if parsed.is_empty() {
+        return Err(ClientError::InvalidPin(pins.to_owned()));
+    }
+    Ok(parsed)
+}
+
+/// Checks that the server `response` came from has one of the `pins`' keys. It has to have been
+/// sent by a client built with [`Config::http_client`](crate::Config::http_client).
+///
+/// # Errors
+/// Returns [`ClientError::PinMismatch`] if the server's key isn't pinned, or it wasn't connected to
+/// with TLS.
+pub fn check(pins: &[Pin], response: &Response) -> Result<()> {
+    let pin = response
+        .extensions()
+        .get::<TlsInfo>()
+        .and_then(TlsInfo::peer_certificate)
+        .and_then(Pin::of);
+    if pin.is_some_and(|pin| pins.contains(&pin)) {
+        Ok(())
+    } else {
+        let host = response.url().host_str().unwrap_or_default();
+        tracing::warn!("{host} isn't using a pinned key (its key is {pin:?})");
+        Err(ClientError::PinMismatch(host.to_owned()))
+    }
+}
+
+/// Finds the `SubjectPublicKeyInfo` in a DER encoded X.509 certificate, including its header.
+fn public_key_info(certificate: &[u8]) -> Option<&[u8]> {
+    let (SEQUENCE, certificate, _) = der_element(certificate)? else {
+        return None;
+    };
+    let (SEQUENCE, mut fields, _) = der_element(certificate)? else {
+        return None;
+    };
+    // the version, serial number, signature algorithm, issuer, validity and subject come first
+    let before = if fields.first() == Some(&VERSION) {
+        6
+    } else {
+        5
+    };
+    for _ in 0..before {
+        fields = der_element(fields)?.2;
+    }
+    let (SEQUENCE, _, rest) = der_element(fields)? else {
+        return None;
+    };
+    Some(&fields[..fields.len() - rest.len()])
+}
+
+/// Splits the first DER element off of `der`, returning its tag, its contents and what's after
+/// it.
+fn der_element(der: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = der.split_first()?;
+    let (&len, rest) = rest.split_first()?;
+    // longer lengths are a count of the bytes holding them
+    let (len, rest) = if len < 0x80 {
+        (usize::from(len), rest)
+    } else {
+        let bytes = usize::from(len & 0x7f);
+        if bytes > std::mem::size_of::<usize>() || bytes > rest.len() {
+            return None;
+        }
+        let (len, rest) = rest.split_at(bytes);
+        let len = len.iter().fold(0, |len, &b| len << 8 | usize::from(b));
+        (len, rest)
+    };
+    (len <= rest.len()).then(|| {
+        let (contents, rest) = rest.split_at(len);
+        (tag, contents, rest)
+    })
+}
@@ -1,13 +1,28 @@
-// use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
-use common::GameId;
-// use rustpython_vm as vm;
-use tokio::sync::{mpsc, oneshot};
-// use vm::py_compile;
+use common::{GameId, GameInfo};
+use rustpython_vm::{self as vm, builtins::PyStrRef, scope::Scope, VirtualMachine};
+use tokio::sync::{mpsc, oneshot, watch};
 
-use crate::Ctx;
+use crate::manifest::Manifest;
+use crate::{ClientError, Config, Ctx, GameStatus};
 
-pub enum Error {}
+/// How often [`watch_dev_hooks`] checks the hook files for changes.
+pub const DEV_HOOKS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Why a hook failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("the game isn't in the game list anymore")]
+    NoGame,
+    /// The hooks didn't compile, or raised an exception.
+    #[error("{0}")]
+    Python(String),
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RequestTy {
@@ -25,46 +40,418 @@ impl RequestTy {
     }
 }
 
+/// The file a game's hooks are loaded from in developer mode: `<id>.py` in `dir`.
+pub fn dev_hooks_path(dir: &Path, id: GameId) -> PathBuf {
+    dir.join(format!("{id}.py"))
+}
+
+/// A game's hooks, from the game list or from a local file in developer mode.
+///
+/// In developer mode (when [`Config::dev_hooks_dir`] is set), they're read from the game's
+/// [file](dev_hooks_path) if it has one, so hook authors can try changes without updating the
+/// server.
+pub fn hooks_source<'a>(config: &Config, info: &'a GameInfo) -> Cow<'a, str> {
+    let Some(dir) = config.dev_hooks_dir() else {
+        return Cow::Borrowed(&info.hooks);
+    };
+    let path = dev_hooks_path(&dir, info.id);
+    match std::fs::read_to_string(&path) {
+        Ok(hooks) => Cow::Owned(hooks),
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(
+                    "using the server's hooks for {}: can't read {path:?}: {e}",
+                    info.name
+                );
+            }
+            Cow::Borrowed(&info.hooks)
+        }
+    }
+}
+
+/// A SHA-256 of a game's hooks (see [`hooks_source`]), to tell whether they've changed since its
+/// `post_install` hook last ran.
+pub fn hooks_hash(config: &Config, info: &GameInfo) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(hooks_source(config, info).as_bytes()).into()
+}
+
+/// Whether an installed game's `post_install` hook last ran from the hooks it has now, so running
+/// it again wouldn't do anything new.
+pub fn setup_is_up_to_date(config: &Config, info: &GameInfo) -> bool {
+    Manifest::load(&config.game_dir(info.id))
+        .and_then(|m| m.post_install_hooks)
+        .is_some_and(|hash| hash == hooks_hash(config, info))
+}
+
+/// Compiles a game's hooks (see [`hooks_source`]) to check them for syntax errors, returning the
+/// error if there is one.
+pub fn check_hooks(config: &Config, info: &GameInfo) -> Option<String> {
+    use rustpython_vm::compiler::{compile, CompileOpts, Mode};
+
+    compile(
+        &hooks_source(config, info),
+        Mode::Exec,
+        format!("hooks-for-{}.py", info.id),
+        CompileOpts::default(),
+    )
+    .err()
+    .map(|e| e.to_string())
+}
+
+/// What one of a game's hooks is doing, or how the last one to run went wrong. Kept in
+/// [`Ctx::hooks`] by [`run_hook`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookStatus {
+    Running(RequestTy),
+    Failed { hook: RequestTy, error: String },
+}
+
 pub struct Request {
     pub ty: RequestTy,
     pub id: GameId,
+    /// Recompile the game's hooks from its current info instead of reusing the compiled ones.
+    /// Hooks are always recompiled in developer mode, so edits to their files apply.
+    pub reload: bool,
     pub finish: oneshot::Sender<Result<(), Error>>,
 }
 
-#[allow(
-    clippy::module_name_repetitions,
-    clippy::missing_panics_doc,
-    clippy::needless_pass_by_value
-)]
-pub fn py_loop(mut _rx: mpsc::UnboundedReceiver<Request>, _ctx: Ctx) -> ! {
-    // let interp = vm::Interpreter::with_init(vm::Settings::default(), |vm| {
-    //     vm.add_native_modules(vm::stdlib::get_module_inits());
-    // });
-
-    // let mut scope_map = HashMap::new();
-
-    // interp.enter(|vm| loop {
-    //     let req = rx.blocking_recv().unwrap();
-
-    //     let scope = scope_map.entry(req.id).or_insert_with(|| {
-    //         let scope = vm.new_scope_with_builtins();
-
-    //         let game = ctx.config.games.get(&req.id).unwrap().clone();
-
-    //         vm.run_code_obj(
-    //             vm.compile(
-    //                 &game.info.hooks,
-    //                 vm::compiler::Mode::Exec,
-    //                 format!("hooks-for-{}.py", game.info.id),
-    //             )
-    //             .unwrap(),
-    //             scope.clone(),
-    //         )
-    //         .unwrap();
-    //         scope
-    //     });
-
-    //     let func = req.ty.func();
-    // })
-    todo!();
+/// Runs one of a game's hooks on the Python thread and waits for it to return.
+///
+/// The hook is shown as running in [`Ctx::hooks`] until it returns. If it fails, the error is
+/// kept there for the game until one of its hooks runs again.
+///
+/// # Errors
+/// Returns [`ClientError::PythonUnavailable`] if the Python thread isn't running, or
+/// [`ClientError::HookFailed`] if the hook raised an exception.
+pub async fn run_hook(ctx: &Ctx, id: GameId, ty: RequestTy, reload: bool) -> crate::Result<()> {
+    ctx.hooks.insert(id, HookStatus::Running(ty));
+    let res = async {
+        let (finish, finished) = oneshot::channel();
+        ctx.py_tx
+            .send(Request {
+                ty,
+                id,
+                reload,
+                finish,
+            })
+            .map_err(|_| ClientError::PythonUnavailable)?;
+        match finished.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(ClientError::HookFailed(e.to_string())),
+            Err(_) => Err(ClientError::PythonUnavailable),
+        }
+    }
+    .await;
+
+    if let Err(e) = &res {
+        tracing::warn!("{} hook of game {id} failed: {e}", ty.func());
+        ctx.hooks.insert(
+            id,
+            HookStatus::Failed {
+                hook: ty,
+                error: e.to_string(),
+            },
+        );
+    } else {
+        ctx.hooks.remove(&id);
+    }
+    res
+}
+
+/// Runs a game's `post_install` hook, unless it already ran from the same hooks (see
+/// [`setup_is_up_to_date`]) and `force` isn't set. Returns whether it ran.
+///
+/// Once it's run, the hooks it ran from are recorded in the game's [`Manifest`].
+///
+/// # Errors
+/// Returns an error if the hook can't be run, see [`run_hook`].
+pub async fn run_post_install(
+    ctx: &Ctx,
+    id: GameId,
+    reload: bool,
+    force: bool,
+) -> crate::Result<bool> {
+    let Some(info) = ctx.config.games().get(&id).map(|g| g.info.clone()) else {
+        return Ok(false);
+    };
+    if !force && setup_is_up_to_date(&ctx.config, &info) {
+        tracing::info!(
+            "{}'s setup is up to date; not running post_install",
+            info.name
+        );
+        return Ok(false);
+    }
+    // hashed first, so hooks that change while it runs are run again next time
+    let hash = hooks_hash(&ctx.config, &info);
+    run_hook(ctx, id, RequestTy::PostInstall, reload).await?;
+
+    let game_dir = ctx.config.game_dir(id);
+    let mut manifest = Manifest::load(&game_dir).unwrap_or_default();
+    manifest.post_install_hooks = Some(hash);
+    if let Err(e) = manifest.save(&game_dir) {
+        tracing::warn!("couldn't record that {}'s setup ran: {e}", info.name);
+    }
+    Ok(true)
+}
+
+/// Runs a game with its `pre_run` hook before it and its `post_run` hook after, returning what
+/// `run` returns.
+///
+/// The hooks sync the game's saves, so a game whose hook fails still runs: the failure is shown
+/// in [`Ctx::hooks`], and a failed `pre_run` stays there even if `post_run` works.
+pub async fn run_with_hooks<T>(ctx: &Ctx, id: GameId, run: impl Future<Output = T>) -> T {
+    let pre_run = run_hook(ctx, id, RequestTy::PreRun, false).await;
+    let res = run.await;
+    let post_run = run_hook(ctx, id, RequestTy::PostRun, false).await;
+    if let (Err(e), Ok(())) = (pre_run, post_run) {
+        ctx.hooks.insert(
+            id,
+            HookStatus::Failed {
+                hook: RequestTy::PreRun,
+                error: e.to_string(),
+            },
+        );
+    }
+    res
+}
+
+/// Sent from the Python thread while a hook is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    /// A hook called `report_progress(done, total)`.
+    Progress { id: GameId, done: u64, total: u64 },
+    /// A hook returned, so its progress won't change anymore.
+    Finished { id: GameId },
+}
+
+/// Shows progress reported by hooks as [`GameStatus::Installing`], the same way extraction
+/// progress is shown. Once the hook returns, the game goes back to the status it had before.
+pub async fn handle_responses(ctx: Ctx, mut rx: mpsc::UnboundedReceiver<Response>) {
+    let games = ctx.config.games();
+    let mut progress = HashMap::new();
+
+    while let Some(res) = rx.recv().await {
+        match res {
+            Response::Progress { id, done, total } => {
+                let (tx, _) = progress.entry(id).or_insert_with(|| {
+                    let (tx, rx) = watch::channel((done, total));
+                    let replaced = games.get_mut(&id).map(|mut game| {
+                        let replaced = game.status.clone();
+                        ctx.config.set_status(&mut game, GameStatus::Installing(rx));
+                        replaced
+                    });
+                    (tx, replaced)
+                });
+                tx.send_replace((done, total));
+            }
+            Response::Finished { id } => {
+                let Some((_, Some(replaced))) = progress.remove(&id) else {
+                    continue;
+                };
+                if let Some(mut game) = games.get_mut(&id) {
+                    // unless something else has changed it since
+                    if matches!(game.status, GameStatus::Installing(_)) {
+                        ctx.config.set_status(&mut game, replaced);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Recompiles a game's hooks whenever its file in [`Config::dev_hooks_dir`] changes.
+///
+/// The files are checked every [`DEV_HOOKS_POLL_INTERVAL`], so mistakes show up right away.
+/// Does nothing while developer mode is off.
+pub async fn watch_dev_hooks(config: Config) {
+    let mut modified = BTreeMap::new();
+    let mut interval = tokio::time::interval(DEV_HOOKS_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(dir) = config.dev_hooks_dir() else {
+            continue;
+        };
+        reload_changed_hooks(&config, &dir, &mut modified);
+    }
+}
+
+/// Recompiles the hooks of games whose hook file in `dir` was added, changed or removed since it
+/// was last seen in `modified`, returning the games that were recompiled.
+pub fn reload_changed_hooks(
+    config: &Config,
+    dir: &Path,
+    modified: &mut BTreeMap<GameId, Option<SystemTime>>,
+) -> Vec<GameId> {
+    let games = config.games();
+    let ids = games.iter().map(|g| *g.key()).collect::<Vec<_>>();
+    let mut reloaded = Vec::new();
+    for id in ids {
+        let time = std::fs::metadata(dev_hooks_path(dir, id))
+            .and_then(|m| m.modified())
+            .ok();
+        match modified.insert(id, time) {
+            Some(last) if last == time => continue,
+            // games without a file yet use the server's hooks, which were checked already
+            None if time.is_none() => continue,
+            _ => {}
+        }
+        let Some(info) = games.get(&id).map(|g| g.info.clone()) else {
+            continue;
+        };
+        let error = check_hooks(config, &info);
+        if let Some(e) = &error {
+            tracing::warn!("{}'s hooks are broken: {e}", info.name);
+        } else {
+            tracing::info!("reloaded {}'s hooks", info.name);
+        }
+        if let Some(mut game) = games.get_mut(&id) {
+            game.hooks_error = error;
+        }
+        reloaded.push(id);
+    }
+    reloaded
+}
+
+/// Runs hooks on the Python thread as they're requested, until every sender of `rx` is dropped.
+///
+/// Each game's hooks are compiled the first time one of them runs and kept for the next, unless
+/// the request [reloads](Request::reload) them. Besides Python's builtins, hooks can call
+/// `report_progress(done, total)`, sent on `responses` for [`handle_responses`] to show, and
+/// `remove_glob(pattern)`, which removes files in the game's directory like
+/// [`remove_glob`](crate::cleanup::remove_glob), and read the game's `game_dir` and `save_dir`
+/// as strings. A game that doesn't have the requested hook doesn't need to do anything for it.
+#[allow(clippy::module_name_repetitions, clippy::needless_pass_by_value)]
+pub fn py_loop(
+    mut rx: mpsc::UnboundedReceiver<Request>,
+    responses: mpsc::UnboundedSender<Response>,
+    ctx: Ctx,
+) {
+    let interp = vm::Interpreter::with_init(vm::Settings::default(), |vm| {
+        vm.add_native_modules(vm::stdlib::get_module_inits());
+    });
+
+    let mut scopes = HashMap::new();
+
+    interp.enter(|vm| {
+        while let Some(req) = rx.blocking_recv() {
+            if req.reload || ctx.config.dev_hooks_dir().is_some() {
+                scopes.remove(&req.id);
+            }
+            let res = run_request(vm, &mut scopes, &req, &responses, &ctx);
+            let _ = responses.send(Response::Finished { id: req.id });
+            // the hook's caller might not be waiting anymore
+            let _ = req.finish.send(res);
+        }
+    });
+}
+
+/// Runs the hook `req` asks for, compiling the game's hooks into a scope first if they aren't in
+/// `scopes`.
+fn run_request(
+    vm: &VirtualMachine,
+    scopes: &mut HashMap<GameId, Scope>,
+    req: &Request,
+    responses: &mpsc::UnboundedSender<Response>,
+    ctx: &Ctx,
+) -> Result<(), Error> {
+    let scope = match scopes.get(&req.id) {
+        Some(scope) => scope.clone(),
+        None => {
+            let info = ctx
+                .config
+                .games()
+                .get(&req.id)
+                .map(|g| g.info.clone())
+                .ok_or(Error::NoGame)?;
+            let scope = load_hooks(vm, &info, responses, ctx)?;
+            scopes.insert(req.id, scope.clone());
+            scope
+        }
+    };
+    // the game can have been moved since its hooks were loaded
+    set_dirs(vm, &scope, req.id, &ctx.config)?;
+
+    let func = scope
+        .globals
+        .get_item_opt(req.ty.func(), vm)
+        .map_err(|e| exception(vm, &e))?;
+    let Some(func) = func else {
+        tracing::debug!("game {} doesn't have a {} hook", req.id, req.ty.func());
+        return Ok(());
+    };
+    tracing::info!("running the {} hook of game {}", req.ty.func(), req.id);
+    func.call((), vm).map_err(|e| exception(vm, &e))?;
+    Ok(())
+}
+
+/// Compiles a game's hooks (see [`hooks_source`]) and runs them, so the scope has their
+/// functions, along with the ones [`py_loop`] gives hooks.
+fn load_hooks(
+    vm: &VirtualMachine,
+    info: &GameInfo,
+    responses: &mpsc::UnboundedSender<Response>,
+    ctx: &Ctx,
+) -> Result<Scope, Error> {
+    let scope = vm.new_scope_with_builtins();
+    let id = info.id;
+
+    let report_progress = vm.new_function("report_progress", {
+        let responses = responses.clone();
+        move |done: u64, total: u64| {
+            let _ = responses.send(Response::Progress { id, done, total });
+        }
+    });
+    let remove_glob = vm.new_function("remove_glob", {
+        let game_dir = ctx.config.game_dir(id);
+        move |pattern: PyStrRef, vm: &VirtualMachine| {
+            crate::cleanup::remove_glob(&game_dir, pattern.as_str())
+                .map_err(|e| vm.new_os_error(e.to_string()))
+        }
+    });
+    for (name, func) in [
+        ("report_progress", report_progress),
+        ("remove_glob", remove_glob),
+    ] {
+        scope
+            .globals
+            .set_item(name, func.into(), vm)
+            .map_err(|e| exception(vm, &e))?;
+    }
+    set_dirs(vm, &scope, id, &ctx.config)?;
+
+    let code = vm
+        .compile(
+            &hooks_source(&ctx.config, info),
+            vm::compiler::Mode::Exec,
+            format!("hooks-for-{id}.py"),
+        )
+        .map_err(|e| Error::Python(e.to_string()))?;
+    vm.run_code_obj(code, scope.clone())
+        .map_err(|e| exception(vm, &e))?;
+    Ok(scope)
+}
+
+/// Sets the `game_dir` and `save_dir` constants of a game's hooks.
+fn set_dirs(vm: &VirtualMachine, scope: &Scope, id: GameId, config: &Config) -> Result<(), Error> {
+    for (name, dir) in [
+        ("game_dir", config.game_dir(id)),
+        ("save_dir", config.save_dir(id)),
+    ] {
+        let dir = vm.ctx.new_str(dir.to_string_lossy().into_owned());
+        scope
+            .globals
+            .set_item(name, dir.into(), vm)
+            .map_err(|e| exception(vm, &e))?;
+    }
+    Ok(())
+}
+
+/// An exception a hook raised, like `ValueError('bad path')`.
+fn exception(vm: &VirtualMachine, exc: &vm::builtins::PyBaseExceptionRef) -> Error {
+    Error::Python(vm::PyObjectRef::from(exc.clone()).repr(vm).map_or_else(
+        |_| "an exception".to_owned(),
+        |repr| repr.as_str().to_owned(),
+    ))
 }
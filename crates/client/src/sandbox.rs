@@ -0,0 +1,294 @@
+//! Running games in a Windows job object, which caps what their processes can use together. It's
+//! meant for shared computers, like a school's lab, running games that aren't trusted.
+//!
+//! Job objects only exist on Windows, so the limits are ignored everywhere else.
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::{Child, Command};
+
+/// What a game's processes can use together. Everything is unlimited by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// The most memory they can commit, in bytes.
+    pub memory: Option<u64>,
+    /// The most processes that can be running at once, counting the game's own.
+    pub processes: Option<u32>,
+    /// The most bytes per second they can send over the network. Needs Windows 10.
+    pub network: Option<u64>,
+}
+
+impl Limits {
+    pub const fn is_unlimited(&self) -> bool {
+        self.memory.is_none() && self.processes.is_none() && self.network.is_none()
+    }
+}
+
+/// Makes a new job with `limits` for a game to be [started](spawn) in.
+///
+/// Returns `None` if there's nothing to limit, or this isn't Windows.
+///
+/// # Errors
+/// Returns an error if the job can't be created with the limits.
+pub fn job(limits: &Limits) -> io::Result<Option<Job>> {
+    if limits.is_unlimited() {
+        return Ok(None);
+    }
+    #[cfg(windows)]
+    {
+        Job::new(limits).map(Some)
+    }
+    #[cfg(not(windows))]
+    {
+        tracing::debug!("not limiting the game: job objects are only on Windows");
+        Ok(None)
+    }
+}
+
+/// Starts `command` with `creation_flags`, in `job` if there is one, so every process it starts
+/// is in the job too. It's started suspended and only resumed once it's in the job, so it can't
+/// start anything before then. Dropping the job doesn't stop the game.
+///
+/// # Errors
+/// Returns an error if the process can't be started, put in the job or resumed. It's killed if
+/// it was started.
+pub fn spawn(command: &mut Command, creation_flags: u32, job: Option<&Job>) -> io::Result<Child> {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::System::Threading::CREATE_SUSPENDED;
+
+        let Some(job) = job else {
+            return command.creation_flags(creation_flags).spawn();
+        };
+        let mut child = command
+            .creation_flags(creation_flags | CREATE_SUSPENDED)
+            .spawn()?;
+        let contained = job.assign(&child).and_then(|()| {
+            let pid = child.id().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "the game has already exited")
+            })?;
+            resume(pid)
+        });
+        if let Err(e) = contained {
+            let _ = child.start_kill();
+            return Err(io::Error::new(
+                e.kind(),
+                format!("couldn't limit what the game can use: {e}"),
+            ));
+        }
+        Ok(child)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = (creation_flags, job);
+        command.spawn()
+    }
+}
+
+/// Resumes the threads of the process `pid`, which was started suspended.
+#[cfg(windows)]
+fn resume(pid: u32) -> io::Result<()> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use windows_sys::Win32::System::Threading::{OpenThread, ResumeThread, THREAD_SUSPEND_RESUME};
+
+    // SAFETY: it's a snapshot of every thread, closed below
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: it's plain data, filled in by `Thread32First`
+    let mut entry: THREADENTRY32 = unsafe { std::mem::zeroed() };
+    entry.dwSize = u32::try_from(std::mem::size_of::<THREADENTRY32>()).unwrap_or(u32::MAX);
+    let mut res = Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "the game has already exited",
+    ));
+    // SAFETY: the snapshot is open, and `entry`'s size is set
+    let mut more = unsafe { Thread32First(snapshot, &mut entry) } != 0;
+    while more {
+        if entry.th32OwnerProcessID == pid {
+            // SAFETY: the thread is only resumed, and its handle is closed right after
+            res = unsafe {
+                let thread = OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID);
+                if thread == 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    let resumed = ResumeThread(thread);
+                    let res = if resumed == u32::MAX {
+                        Err(io::Error::last_os_error())
+                    } else {
+                        Ok(())
+                    };
+                    CloseHandle(thread);
+                    res
+                }
+            };
+            if res.is_err() {
+                break;
+            }
+        }
+        // SAFETY: as for `Thread32First`
+        more = unsafe { Thread32Next(snapshot, &mut entry) } != 0;
+    }
+    // SAFETY: the snapshot is open, and isn't used after this
+    unsafe { CloseHandle(snapshot) };
+    res
+}
+
+/// A Windows job object, from [`job`].
+#[derive(Debug)]
+pub struct Job {
+    #[cfg(windows)]
+    handle: windows_sys::Win32::Foundation::HANDLE,
+}
+
+#[cfg(not(windows))]
+impl Job {
+    /// The IDs of the processes in the job that are still running.
+    ///
+    /// # Errors
+    /// Returns an error if the job can't be asked.
+    pub fn process_ids(&self) -> io::Result<Vec<u32>> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+#[cfg(windows)]
+impl Job {
+    fn new(limits: &Limits) -> io::Result<Self> {
+        use windows_sys::Win32::System::JobObjects::{
+            CreateJobObjectW, JobObjectExtendedLimitInformation,
+            JobObjectNetRateControlInformation, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOBOBJECT_NET_RATE_CONTROL_INFORMATION, JOB_OBJECT_LIMIT_ACTIVE_PROCESS,
+            JOB_OBJECT_LIMIT_JOB_MEMORY, JOB_OBJECT_NET_RATE_CONTROL_ENABLE,
+            JOB_OBJECT_NET_RATE_CONTROL_MAX_BANDWIDTH,
+        };
+
+        // SAFETY: the job gets the default security attributes and no name
+        let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+        if handle == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // closes the handle if setting the limits fails
+        let job = Self { handle };
+
+        // SAFETY: it's plain data, for which all zeroes means no limits
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = unsafe { std::mem::zeroed() };
+        if let Some(memory) = limits.memory {
+            info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_JOB_MEMORY;
+            info.JobMemoryLimit = usize::try_from(memory).unwrap_or(usize::MAX);
+        }
+        if let Some(processes) = limits.processes {
+            info.BasicLimitInformation.LimitFlags |= JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+            info.BasicLimitInformation.ActiveProcessLimit = processes;
+        }
+        job.set(JobObjectExtendedLimitInformation, &info)?;
+        if let Some(network) = limits.network {
+            let info = JOBOBJECT_NET_RATE_CONTROL_INFORMATION {
+                MaxBandwidth: network,
+                ControlFlags: JOB_OBJECT_NET_RATE_CONTROL_ENABLE
+                    | JOB_OBJECT_NET_RATE_CONTROL_MAX_BANDWIDTH,
+                DscpTag: 0,
+            };
+            job.set(JobObjectNetRateControlInformation, &info)?;
+        }
+        Ok(job)
+    }
+
+    /// Sets the job's information of `class`, which `info` has to be the struct for.
+    fn set<T>(
+        &self,
+        class: windows_sys::Win32::System::JobObjects::JOBOBJECTINFOCLASS,
+        info: &T,
+    ) -> io::Result<()> {
+        use windows_sys::Win32::System::JobObjects::SetInformationJobObject;
+
+        let size = u32::try_from(std::mem::size_of::<T>()).unwrap_or(u32::MAX);
+        // SAFETY: `info` is valid for `size` bytes, and is the struct `class` reads
+        let ok = unsafe {
+            SetInformationJobObject(self.handle, class, std::ptr::from_ref(info).cast(), size)
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn assign(&self, child: &Child) -> io::Result<()> {
+        use windows_sys::Win32::System::JobObjects::AssignProcessToJobObject;
+
+        let Some(process) = child.raw_handle() else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "the game has already exited",
+            ));
+        };
+        // SAFETY: both handles are open, the process's for as long as `child` is borrowed
+        let ok = unsafe { AssignProcessToJobObject(self.handle, process as _) };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// The IDs of the processes in the job that are still running.
+    ///
+    /// # Errors
+    /// Returns an error if the job can't be asked.
+    pub fn process_ids(&self) -> io::Result<Vec<u32>> {
+        use std::mem::{offset_of, size_of};
+        use windows_sys::Win32::Foundation::ERROR_MORE_DATA;
+        use windows_sys::Win32::System::JobObjects::{
+            JobObjectBasicProcessIdList, QueryInformationJobObject, JOBOBJECT_BASIC_PROCESS_ID_LIST,
+        };
+
+        /// How many IDs are asked for. A game with more than this many processes only has this
+        /// many counted.
+        const MAX_IDS: usize = 256;
+
+        // the list's header is followed by as many IDs as fit, each the size of a `usize`
+        let start = offset_of!(JOBOBJECT_BASIC_PROCESS_ID_LIST, ProcessIdList) / size_of::<usize>();
+        let mut buf = vec![0usize; start + MAX_IDS];
+        let size = u32::try_from(buf.len() * size_of::<usize>()).unwrap_or(u32::MAX);
+        // SAFETY: `buf` is valid for `size` bytes and aligned for the list
+        let ok = unsafe {
+            QueryInformationJobObject(
+                self.handle,
+                JobObjectBasicProcessIdList,
+                buf.as_mut_ptr().cast(),
+                size,
+                std::ptr::null_mut(),
+            )
+        };
+        // the IDs that fit are still filled in when there are more
+        if ok == 0 {
+            let e = io::Error::last_os_error();
+            if e.raw_os_error() != i32::try_from(ERROR_MORE_DATA).ok() {
+                return Err(e);
+            }
+        }
+        // SAFETY: the buffer starts with the list's header
+        let listed = unsafe {
+            (*buf.as_ptr().cast::<JOBOBJECT_BASIC_PROCESS_ID_LIST>()).NumberOfProcessIdsInList
+        };
+        let listed = usize::try_from(listed).unwrap_or(MAX_IDS);
+        Ok(buf[start..]
+            .iter()
+            .take(listed)
+            .filter_map(|&id| u32::try_from(id).ok())
+            .collect())
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Job {
+    fn drop(&mut self) {
+        // SAFETY: the handle is open, and isn't used after this
+        unsafe { windows_sys::Win32::Foundation::CloseHandle(self.handle) };
+    }
+}
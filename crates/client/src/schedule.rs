@@ -0,0 +1,111 @@
+//! Only downloading during part of the day, like after school, so downloads don't use up a
+//! metered or shared network when it's busy.
+//!
+//! It's off unless [`Config::download_window`](crate::Config::download_window) is set. Downloads
+//! that are running when the window closes stop at their next checkpoint, and are resumed when it
+//! opens again.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+/// A time of day, written like `15:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay {
+    /// Minutes after midnight.
+    minutes: u16,
+}
+
+impl TimeOfDay {
+    /// `None` if it isn't a time, like 25:00.
+    pub const fn new(hour: u16, minute: u16) -> Option<Self> {
+        if hour < 24 && minute < 60 {
+            Some(Self {
+                minutes: hour * 60 + minute,
+            })
+        } else {
+            None
+        }
+    }
+
+    const fn seconds(self) -> u32 {
+        self.minutes as u32 * 60
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .split_once(':')
+            .and_then(|(hour, minute)| Self::new(hour.parse().ok()?, minute.parse().ok()?))
+            .ok_or_else(|| format!("{s:?} isn't a time of day like 15:00"))
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> Self {
+        time.to_string()
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+/// The part of each day downloads can run in. It wraps around midnight if it ends before it
+/// starts, like 22:00 to 06:00, and is the whole day if they're the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadWindow {
+    pub start: TimeOfDay,
+    pub end: TimeOfDay,
+}
+
+impl DownloadWindow {
+    /// Whether downloads can run at `now`, in seconds after midnight.
+    pub const fn is_open(&self, now: u32) -> bool {
+        let (start, end) = (self.start.seconds(), self.end.seconds());
+        if start <= end {
+            start == end || (start <= now && now < end)
+        } else {
+            now >= start || now < end
+        }
+    }
+
+    /// How long it is from `now` until the window next opens or closes, or `None` if it's open
+    /// all day.
+    pub fn next_change(&self, now: u32) -> Option<Duration> {
+        if self.start == self.end {
+            return None;
+        }
+        let next = if self.is_open(now) {
+            self.end
+        } else {
+            self.start
+        };
+        let wait = (next.seconds() + SECONDS_PER_DAY - now % SECONDS_PER_DAY) % SECONDS_PER_DAY;
+        Some(Duration::from_secs(wait.into()))
+    }
+}
+
+/// The local time, in seconds after midnight.
+pub fn now() -> u32 {
+    chrono::Local::now().num_seconds_from_midnight()
+}
@@ -0,0 +1,260 @@
+//! The places a game's archive can be downloaded from.
+//!
+//! Each host implements [`DownloadSource`], which only has to start the download. Resuming,
+//! progress, pausing and retries are handled the same way for every source by
+//! [`download_game`](crate::download::download_game).
+
+use std::fmt;
+use std::future::Future;
+
+use crate::{ClientError, Ctx, PartialDownload, Result};
+use common::GameSource;
+use reqwest::header::{CONTENT_LENGTH, IF_RANGE, RANGE};
+use reqwest::Response;
+use tl::ParserOptions;
+
+/// Somewhere a game's archive can be downloaded from.
+pub trait DownloadSource: fmt::Display + Sync {
+    /// Requests the archive, asking for the rest of it if `resume` is set.
+    ///
+    /// The server can send the whole archive instead of the rest, which the caller checks for, so
+    /// sources don't have to.
+    ///
+    /// # Errors
+    /// Returns an error if the host can't be reached or doesn't have the archive.
+    fn fetch(
+        &self,
+        ctx: &Ctx,
+        resume: Option<&PartialDownload>,
+    ) -> impl Future<Output = Result<Response>> + Send;
+}
+
+/// A file shared from Google Drive, by its ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoogleDrive(pub String);
+
+/// A plain HTTP(S) URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Direct(pub String);
+
+impl fmt::Display for GoogleDrive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Google Drive file {}", self.0)
+    }
+}
+
+impl fmt::Display for Direct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// What Google Drive sends when a file is asked for.
+enum DriveResponse {
+    /// The file itself.
+    File(Response),
+    /// A page asking to confirm the download, and the link it confirms with.
+    Confirm(DownloadLink),
+}
+
+impl GoogleDrive {
+    fn url(&self, ctx: &Ctx) -> String {
+        format!(
+            "{}/uc?export=download&id={}",
+            ctx.config.drive_url(),
+            self.0
+        )
+    }
+
+    async fn request(&self, ctx: &Ctx, resume: Option<&PartialDownload>) -> Result<DriveResponse> {
+        let response = get(ctx, &self.url(ctx), resume).await?;
+
+        // google drive asks for confirmation before downloading files too big to virus scan
+        if !response
+            .headers()
+            .get("Content-Type")
+            .is_some_and(|h| h.as_ref().starts_with(b"text/html"))
+        {
+            return Ok(DriveResponse::File(response));
+        }
+        let link = parse_drive_download_link(&response.text().await?)?;
+        Ok(DriveResponse::Confirm(link))
+    }
+}
+
+impl DownloadSource for GoogleDrive {
+    async fn fetch(&self, ctx: &Ctx, resume: Option<&PartialDownload>) -> Result<Response> {
+        match self.request(ctx, resume).await? {
+            DriveResponse::File(response) => Ok(response),
+            DriveResponse::Confirm(link) => {
+                tracing::info!("real google drive download URL: {}", link.url);
+                get(ctx, &link.url, resume).await
+            }
+        }
+    }
+}
+
+impl DownloadSource for Direct {
+    async fn fetch(&self, ctx: &Ctx, resume: Option<&PartialDownload>) -> Result<Response> {
+        get(ctx, &self.0, resume).await
+    }
+}
+
+/// The sources games can list, each downloaded by its own [`DownloadSource`].
+impl DownloadSource for GameSource {
+    async fn fetch(&self, ctx: &Ctx, resume: Option<&PartialDownload>) -> Result<Response> {
+        match self {
+            Self::GoogleDrive(id) => GoogleDrive(id.clone()).fetch(ctx, resume).await,
+            Self::Direct(url) => Direct(url.clone()).fetch(ctx, resume).await,
+        }
+    }
+}
+
+/// How the URL in a [`DownloadLink`] was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStrategy {
+    /// The source's URL is the download itself.
+    Direct,
+    /// Google Drive sent the file right away, without asking to confirm.
+    DriveUnconfirmed,
+    /// The action of the download form on Google Drive's confirmation page.
+    DriveFormAction,
+    /// The download form's action, with the query from its hidden inputs.
+    DriveHiddenInputs,
+}
+
+impl fmt::Display for LinkStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Direct => "direct link",
+            Self::DriveUnconfirmed => "Google Drive link without confirmation",
+            Self::DriveFormAction => "Google Drive confirmation form's action",
+            Self::DriveHiddenInputs => "Google Drive confirmation form's hidden inputs",
+        })
+    }
+}
+
+/// The URL a source's archive is really downloaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadLink {
+    pub url: String,
+    pub strategy: LinkStrategy,
+}
+
+/// Finds the URL a source's archive is downloaded from without downloading it, to tell whether a
+/// failing download is the host's fault.
+///
+/// # Errors
+/// Returns an error if the host can't be reached, doesn't have the archive, or shows a page
+/// without a download link (see [`parse_drive_download_url`]).
+pub async fn resolve_link(ctx: &Ctx, source: &GameSource) -> Result<DownloadLink> {
+    match source {
+        GameSource::Direct(url) => Ok(DownloadLink {
+            url: url.clone(),
+            strategy: LinkStrategy::Direct,
+        }),
+        GameSource::GoogleDrive(id) => {
+            let drive = GoogleDrive(id.clone());
+            match drive.request(ctx, None).await? {
+                // dropping the response stops the download
+                DriveResponse::File(response) => {
+                    response.error_for_status()?;
+                    Ok(DownloadLink {
+                        url: drive.url(ctx),
+                        strategy: LinkStrategy::DriveUnconfirmed,
+                    })
+                }
+                DriveResponse::Confirm(link) => Ok(link),
+            }
+        }
+    }
+}
+
+/// Asks a source how big its archive is without downloading it, with a HEAD request to its
+/// [real link](resolve_link). Returns `None` if the host doesn't say.
+///
+/// # Errors
+/// Returns an error if the link can't be resolved, or the host doesn't have the archive.
+pub async fn archive_size(ctx: &Ctx, source: &GameSource) -> Result<Option<u64>> {
+    let link = resolve_link(ctx, source).await?;
+    let response = ctx
+        .client
+        .head(&link.url)
+        .send()
+        .await
+        .map_err(|e| ctx.config.network_error(e))?
+        .error_for_status()?;
+    // `Response::content_length` is the size of the body, which a HEAD response doesn't have
+    Ok(response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok()?.parse().ok()))
+}
+
+/// Sends a GET request for `url`, asking for the rest of the file if `resume` is set. The rest is
+/// only sent if the file hasn't changed since.
+async fn get(ctx: &Ctx, url: &str, resume: Option<&PartialDownload>) -> Result<Response> {
+    let mut request = ctx.client.get(url);
+    if let Some(partial) = resume {
+        request = request
+            .header(RANGE, format!("bytes={}-", partial.offset))
+            .header(IF_RANGE, partial.validator().unwrap_or_default());
+    }
+    request
+        .send()
+        .await
+        .map_err(|e| ctx.config.network_error(e))
+}
+
+/// Finds the real download URL in the page Google Drive shows instead of files it can't virus
+/// scan.
+///
+/// The page has a form that downloads the file anyway, either with the whole URL as its action or
+/// with the query in hidden inputs.
+///
+/// # Errors
+/// Returns [`ClientError::BadDrive`] if there's no download form, which usually means Drive showed
+/// an error page (like when a file has been downloaded too many times).
+pub fn parse_drive_download_url(html: &str) -> Result<String> {
+    parse_drive_download_link(html).map(|link| link.url)
+}
+
+/// Like [`parse_drive_download_url`], but also says which part of the form the URL came from.
+///
+/// # Errors
+/// Returns [`ClientError::BadDrive`] if there's no download form.
+pub fn parse_drive_download_link(html: &str) -> Result<DownloadLink> {
+    let dom = tl::parse(html, ParserOptions::default())?;
+    let parser = dom.parser();
+    let form = dom
+        .get_element_by_id("download-form")
+        .and_then(|form| form.get(parser))
+        .and_then(tl::Node::as_tag)
+        .ok_or(ClientError::BadDrive)?;
+    let attr = |tag: &tl::HTMLTag, name| {
+        tag.attributes()
+            .get(name)
+            .flatten()
+            .map(|v| v.as_utf8_str().replace("&amp;", "&"))
+    };
+
+    let action = attr(form, "action").ok_or(ClientError::BadDrive)?;
+    let mut url = reqwest::Url::parse(&action).map_err(|_| ClientError::BadDrive)?;
+    let hidden = form
+        .children()
+        .all(parser)
+        .iter()
+        .filter_map(tl::Node::as_tag)
+        .filter(|tag| tag.name() == "input" && attr(tag, "type").as_deref() == Some("hidden"));
+    let mut strategy = LinkStrategy::DriveFormAction;
+    for input in hidden {
+        if let (Some(name), Some(value)) = (attr(input, "name"), attr(input, "value")) {
+            url.query_pairs_mut().append_pair(&name, &value);
+            strategy = LinkStrategy::DriveHiddenInputs;
+        }
+    }
+    Ok(DownloadLink {
+        url: url.into(),
+        strategy,
+    })
+}
@@ -0,0 +1,121 @@
+//! The system tray icon, which shows what's downloading and keeps the app running while its
+//! window is hidden.
+
+use std::{fmt::Write, time::Duration};
+
+use bramlett::Ctx;
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuItem},
+    ClickType, Icon, TrayIcon, TrayIconBuilder, TrayIconEvent,
+};
+use wry::application::event_loop::EventLoopProxy;
+
+/// How often the tooltip is updated with the downloads' progress.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Something done with the tray icon or its menu, sent to the event loop by [`forward_events`].
+#[derive(Debug)]
+pub enum TrayEvent {
+    Icon(TrayIconEvent),
+    Menu(MenuEvent),
+}
+
+/// What the window should do after a [`TrayEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Show,
+    Quit,
+}
+
+pub struct Tray {
+    icon: TrayIcon,
+    open: MenuItem,
+    pause: MenuItem,
+    quit: MenuItem,
+}
+
+impl Tray {
+    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let image =
+            image::load_from_memory(include_bytes!("../frontend/static/favicon.png"))?.into_rgba8();
+        let (width, height) = image.dimensions();
+        let icon = Icon::from_rgba(image.into_raw(), width, height)?;
+
+        let open = MenuItem::new("Open", true, None);
+        let pause = MenuItem::new("Pause all", true, None);
+        let quit = MenuItem::new("Quit", true, None);
+        let menu = Menu::new();
+        menu.append_items(&[&open, &pause, &quit])?;
+
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip("Bramlett's Games")
+            .with_icon(icon)
+            .build()?;
+        Ok(Self {
+            icon,
+            open,
+            pause,
+            quit,
+        })
+    }
+
+    /// Shows what's downloading and installing in the tooltip, like
+    /// "2 downloading, 1 installing (40%)".
+    pub fn refresh(&self, ctx: &Ctx) {
+        let activity = ctx.config.activity();
+        let mut tooltip = format!("Bramlett's Games: {activity}");
+        if let Some(percent) = activity.percent() {
+            let _ = write!(tooltip, " ({percent}%)");
+        }
+        if ctx.pause.is_paused() {
+            tooltip.push_str(", paused");
+        }
+        if let Err(e) = self.icon.set_tooltip(Some(tooltip)) {
+            tracing::warn!("failed to update the tray tooltip: {e}");
+        }
+    }
+
+    /// Pauses or resumes downloads from the menu, and tells the window what to do for the rest.
+    pub fn handle(&self, ctx: &Ctx, event: &TrayEvent) -> Option<Action> {
+        match event {
+            TrayEvent::Icon(e) if e.click_type == ClickType::Left => Some(Action::Show),
+            TrayEvent::Icon(_) => None,
+            TrayEvent::Menu(e) if e.id == *self.open.id() => Some(Action::Show),
+            TrayEvent::Menu(e) if e.id == *self.quit.id() => Some(Action::Quit),
+            TrayEvent::Menu(e) if e.id == *self.pause.id() => {
+                let paused = !ctx.pause.is_paused();
+                tracing::info!(
+                    "{} downloads from the tray",
+                    if paused { "pausing" } else { "resuming" }
+                );
+                ctx.pause.set(paused);
+                self.pause
+                    .set_text(if paused { "Resume all" } else { "Pause all" });
+                self.refresh(ctx);
+                None
+            }
+            TrayEvent::Menu(_) => None,
+        }
+    }
+}
+
+/// Sends the tray's events to the event loop, so it wakes up for them.
+pub fn forward_events(proxy: &EventLoopProxy<TrayEvent>) {
+    let icon_proxy = proxy.clone();
+    std::thread::spawn(move || {
+        while let Ok(e) = TrayIconEvent::receiver().recv() {
+            if icon_proxy.send_event(TrayEvent::Icon(e)).is_err() {
+                break;
+            }
+        }
+    });
+    let menu_proxy = proxy.clone();
+    std::thread::spawn(move || {
+        while let Ok(e) = MenuEvent::receiver().recv() {
+            if menu_proxy.send_event(TrayEvent::Menu(e)).is_err() {
+                break;
+            }
+        }
+    });
+}
@@ -0,0 +1,211 @@
+//! How much CPU and memory running games use, counting the processes they start (like a
+//! launcher starting the real game). The same processes decide when a game has stopped.
+//!
+//! A game in a [job](crate::sandbox::Job) is the processes in the job instead.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::sandbox::Job;
+use common::GameId;
+use dashmap::DashMap;
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, ProcessStatus, System, SystemExt};
+
+/// How often a running game's usage is measured.
+pub const USAGE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How many measurements go by between looking for processes a game started. Finding them means
+/// listing every process, so it's done less often than measuring the ones already found.
+const RESCAN_EVERY: u32 = 5;
+
+/// What a game's processes are using.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+    /// Percent of the whole CPU, so 100 means every core is busy.
+    pub cpu: f32,
+    /// Bytes of memory.
+    pub memory: u64,
+    /// How many processes were measured: the game and every process it started that's still
+    /// running.
+    pub processes: usize,
+}
+
+/// Measures a process and the processes it started.
+pub struct UsageTracker {
+    sys: System,
+    /// The processes being measured, with when they started.
+    tree: Vec<(Pid, u64)>,
+    /// Every process that's been in the tree and when it started, including ones that have
+    /// exited, since the processes they started still name them as their parent on Windows. The
+    /// start time tells them apart from an unrelated process that's given the same ID later.
+    seen: HashMap<Pid, u64>,
+    measured: u32,
+    cores: f32,
+    job: Option<Arc<Job>>,
+}
+
+impl UsageTracker {
+    /// Measures the process `pid`, which was started at `started`, and the processes it starts.
+    /// It can have exited already, like a launcher that only starts the real game.
+    pub fn new(pid: u32, started: SystemTime) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let cores = std::thread::available_parallelism().map_or(1, usize::from) as f32;
+        let root = Pid::from_u32(pid);
+        let mut sys = System::new();
+        sys.refresh_process_specifics(root, ProcessRefreshKind::new());
+        let start = sys.process(root).map_or_else(
+            // start times are only to the second, so a process started in the same second as
+            // the clock was read can seem to have started a second earlier
+            || {
+                started
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs().saturating_sub(1))
+            },
+            ProcessExt::start_time,
+        );
+        Self {
+            sys,
+            tree: Vec::new(),
+            seen: HashMap::from([(root, start)]),
+            measured: 0,
+            cores,
+            job: None,
+        }
+    }
+
+    /// Measures the processes in `job`, which the process `pid` was put in.
+    pub fn in_job(pid: u32, started: SystemTime, job: Arc<Job>) -> Self {
+        Self {
+            job: Some(job),
+            ..Self::new(pid, started)
+        }
+    }
+
+    /// Measures the processes again. CPU use is averaged since the last measurement, so the
+    /// first one's is 0.
+    ///
+    /// Once every process has exited, [`Usage::processes`] is 0.
+    pub fn measure(&mut self) -> Usage {
+        if self.measured.is_multiple_of(RESCAN_EVERY) {
+            self.rescan();
+        }
+        self.measured = self.measured.wrapping_add(1);
+        self.refresh();
+        // the processes might have started others before exiting, like a launcher does
+        if self.tree.is_empty() {
+            self.rescan();
+            self.refresh();
+        }
+
+        let sys = &self.sys;
+
+        let processes = self.tree.iter().filter_map(|(pid, _)| sys.process(*pid));
+        let (cpu, memory) = processes.fold((0.0, 0), |(cpu, memory), p| {
+            (cpu + p.cpu_usage(), memory + p.memory())
+        });
+        Usage {
+            cpu: cpu / self.cores,
+            memory,
+            processes: self.tree.len(),
+        }
+    }
+
+    /// Forgets the processes that have exited.
+    fn refresh(&mut self) {
+        let sys = &mut self.sys;
+        let kind = ProcessRefreshKind::new().with_cpu();
+        // an exited process is a zombie until its parent notices, which an orphan's new parent
+        // might never do
+        self.tree.retain(|&(pid, start)| {
+            sys.refresh_process_specifics(pid, kind)
+                && sys
+                    .process(pid)
+                    .is_some_and(|p| p.start_time() == start && p.status() != ProcessStatus::Zombie)
+        });
+    }
+
+    /// Finds every process started by the root process, and the processes they started.
+    fn rescan(&mut self) {
+        self.sys
+            .refresh_processes_specifics(ProcessRefreshKind::new().with_cpu());
+        if let Some(job) = &self.job {
+            match job.process_ids() {
+                Ok(ids) => {
+                    self.tree = ids
+                        .into_iter()
+                        .map(Pid::from_u32)
+                        .filter_map(|pid| self.sys.process(pid).map(|p| (pid, p.start_time())))
+                        .collect();
+                    return;
+                }
+                Err(e) => tracing::warn!("failed to list the processes in a game's job: {e}"),
+            }
+        }
+        let sys = &self.sys;
+        // an exited process's ID can be reused by an unrelated one, which isn't the game's
+        self.seen
+            .retain(|pid, start| sys.process(*pid).is_none_or(|p| p.start_time() == *start));
+        let mut parents: Vec<(Pid, u64)> = self.seen.iter().map(|(&p, &s)| (p, s)).collect();
+        let mut i = 0;
+        while let Some(&(parent, parent_start)) = parents.get(i) {
+            for p in sys.processes().values() {
+                // one that started before the parent is the child of an earlier process with its ID
+                if p.parent() == Some(parent)
+                    && p.start_time() >= parent_start
+                    && !self.seen.contains_key(&p.pid())
+                {
+                    self.seen.insert(p.pid(), p.start_time());
+                    parents.push((p.pid(), p.start_time()));
+                }
+            }
+            i += 1;
+        }
+        self.tree = self
+            .seen
+            .iter()
+            .filter(|(pid, start)| {
+                sys.process(**pid)
+                    .is_some_and(|p| p.start_time() == **start)
+            })
+            .map(|(&pid, &start)| (pid, start))
+            .collect();
+    }
+}
+
+/// Measures a game's processes into `usage` every [`USAGE_INTERVAL`], until every one of them has
+/// exited. A game started by a launcher that exits right away is still running until then.
+///
+/// `started` is when the game's process `pid` was started. If the game was put in a `job`, its
+/// processes are the ones in the job.
+pub async fn track(
+    usage: Arc<DashMap<GameId, Usage>>,
+    id: GameId,
+    pid: u32,
+    started: SystemTime,
+    job: Option<Arc<Job>>,
+) {
+    let mut tracker = job.map_or_else(
+        || UsageTracker::new(pid, started),
+        |job| UsageTracker::in_job(pid, started, job),
+    );
+    let mut interval = tokio::time::interval(USAGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        // reading every process can take a moment, so it's kept off the async threads
+        let Ok((t, measured)) = tokio::task::spawn_blocking(move || {
+            let measured = tracker.measure();
+            (tracker, measured)
+        })
+        .await
+        else {
+            break;
+        };
+        tracker = t;
+        if measured.processes == 0 {
+            break;
+        }
+        usage.insert(id, measured);
+    }
+    usage.remove(&id);
+}
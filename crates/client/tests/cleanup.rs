@@ -0,0 +1,171 @@
+//! Checks which leftovers in the games directory get cleaned up.
+
+use bramlett::cleanup::{find_orphans, move_dir, remove_glob, remove_orphans, remove_temp_glob};
+use bramlett::{ClientError, Config, Game, GameStatus, PartialDownload};
+use common::{GameId, GameInfo};
+use std::path::Path;
+
+fn game(id: i32, status: GameStatus) -> Game {
+    Game {
+        info: GameInfo {
+            id: GameId(id),
+            ..Default::default()
+        },
+        status,
+        partial: None,
+        hooks_error: None,
+    }
+}
+
+fn write(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn removes_only_orphans() {
+    let dir = tempfile::tempdir().unwrap();
+    let games_dir = dir.path();
+    let config = Config::default();
+    config.set_games_dir(games_dir.to_path_buf());
+
+    let games = config.games();
+    games.insert(GameId(1), game(1, GameStatus::Ready));
+    games.insert(GameId(2), game(2, GameStatus::NotDownloaded));
+    let mut resumable = game(3, GameStatus::NotDownloaded);
+    resumable.partial = Some(PartialDownload {
+        path: config.staging_dir().join("3.7z.part"),
+        offset: 0,
+        content_length: Some(10),
+        etag: Some("etag".into()),
+        last_modified: None,
+    });
+    games.insert(GameId(3), resumable);
+
+    // kept
+    write(&games_dir.join("1/game.exe"), "installed");
+    write(&games_dir.join("downloads/3.7z.part"), "resumable");
+    write(&games_dir.join("downloads/2.7z"), "failed to extract");
+    write(&games_dir.join("notes.txt"), "not ours");
+    write(&games_dir.join("stuff/file"), "not ours");
+    // removed
+    write(&games_dir.join("1.7z"), "leftover archive");
+    write(&games_dir.join("2/game.exe"), "failed install");
+    write(&games_dir.join("downloads/4.7z.part"), "unknown game");
+    std::fs::create_dir_all(games_dir.join("empty")).unwrap();
+
+    let mut orphans = find_orphans(&config);
+    orphans.sort();
+    let expected = ["1.7z", "2", "downloads/4.7z.part", "empty"].map(|p| games_dir.join(p));
+    assert_eq!(orphans, expected);
+
+    let reclaimed = remove_orphans(&config).unwrap();
+    assert_eq!(
+        reclaimed,
+        ["leftover archive", "failed install", "unknown game"]
+            .iter()
+            .map(|s| s.len() as u64)
+            .sum::<u64>()
+    );
+    assert!(expected.iter().all(|p| !p.exists()));
+    assert!(games_dir.join("1/game.exe").exists());
+    assert!(games_dir.join("downloads/3.7z.part").exists());
+    assert!(games_dir.join("downloads/2.7z").exists());
+    assert!(games_dir.join("notes.txt").exists());
+    assert!(games_dir.join("stuff/file").exists());
+}
+
+#[test]
+fn remove_glob_stays_in_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let game_dir = dir.path().join("game");
+    let outside = dir.path().join("outside.tmp");
+    write(&outside, "not the game's");
+    write(&game_dir.join("a.tmp"), "temp");
+    write(&game_dir.join("game.exe"), "game");
+    write(&game_dir.join("logs/old.log"), "log");
+    write(&game_dir.join("logs/2024/01/crash.log"), "log");
+    write(&game_dir.join("data/keep.log"), "not a log we want gone");
+
+    assert_eq!(remove_glob(&game_dir, "*.tmp").unwrap(), 1);
+    assert_eq!(remove_glob(&game_dir, "logs/**/*.log").unwrap(), 2);
+    assert!(!game_dir.join("a.tmp").exists());
+    assert!(!game_dir.join("logs/old.log").exists());
+    assert!(!game_dir.join("logs/2024/01/crash.log").exists());
+    assert!(game_dir.join("game.exe").exists());
+    assert!(game_dir.join("data/keep.log").exists());
+
+    for pattern in ["../*.tmp", "/tmp/*", "**/../*"] {
+        let res = remove_glob(&game_dir, pattern);
+        assert!(matches!(res, Err(ClientError::InvalidGlob(_))), "{res:?}");
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(dir.path(), game_dir.join("link")).unwrap();
+        assert_eq!(remove_glob(&game_dir, "link/*.tmp").unwrap(), 0);
+        assert!(outside.exists());
+    }
+}
+
+#[test]
+fn temp_globs_only_remove_the_games_files() {
+    let temp = tempfile::tempdir().unwrap();
+    write(&temp.path().join("other.log"), "another program's");
+    write(&temp.path().join("MyGame/a.log"), "log");
+    write(&temp.path().join("MyGame/crash/b.dmp"), "dump");
+    write(&temp.path().join("MyGame.lock"), "lock");
+
+    for pattern in ["**", "*.log", "*/a.log", "../*"] {
+        let res = remove_temp_glob(temp.path(), pattern);
+        assert!(matches!(res, Err(ClientError::InvalidGlob(_))), "{res:?}");
+    }
+    assert_eq!(remove_temp_glob(temp.path(), "MyGame/**").unwrap(), 2);
+    assert_eq!(remove_temp_glob(temp.path(), "MyGame.lock").unwrap(), 1);
+    assert_eq!(remove_temp_glob(temp.path(), "Missing/**").unwrap(), 0);
+    assert!(temp.path().join("other.log").exists());
+}
+
+#[test]
+fn moved_install_is_kept_and_leftovers_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let games_dir = dir.path().join("games");
+    let config = Config::default();
+    config.set_games_dir(games_dir.clone());
+    config.games().insert(GameId(1), game(1, GameStatus::Ready));
+    write(&games_dir.join("1/game.exe"), "installed");
+
+    let other = dir.path().join("other drive");
+    config.set_install_dir(GameId(1), Some(other.clone()));
+    move_dir(&games_dir.join("1"), &config.game_dir(GameId(1))).unwrap();
+    assert_eq!(config.game_dir(GameId(1)), other.join("1"));
+    assert!(other.join("1/game.exe").is_file());
+    assert!(find_orphans(&config).is_empty());
+
+    // something left behind in the games directory
+    write(&games_dir.join("1/stale"), "leftover");
+    assert_eq!(find_orphans(&config), [games_dir.join("1")]);
+
+    config.set_install_dir(GameId(1), None);
+    assert_eq!(config.game_dir(GameId(1)), games_dir.join("1"));
+}
+
+#[test]
+fn removed_games_dir_isnt_made_again() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::default();
+    let games_dir = dir.path().join("removed drive").join("Games");
+    config.set_games_dir(games_dir.clone());
+
+    assert!(!config.games_dir_available());
+    assert!(matches!(
+        config.check_games_dir(),
+        Err(ClientError::GamesDirUnavailable(_))
+    ));
+    assert!(!dir.path().join("removed drive").exists());
+
+    std::fs::create_dir(dir.path().join("removed drive")).unwrap();
+    assert!(config.games_dir_available());
+    config.check_games_dir().unwrap();
+    assert!(games_dir.is_dir());
+}
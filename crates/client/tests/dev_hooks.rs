@@ -0,0 +1,83 @@
+//! Checks that developer mode loads hooks from local files and notices when they change.
+
+use std::collections::BTreeMap;
+
+use bramlett::manifest::Manifest;
+use bramlett::py::{
+    dev_hooks_path, hooks_hash, hooks_source, reload_changed_hooks, setup_is_up_to_date,
+};
+use bramlett::{Config, Game, GameStatus};
+use common::{GameId, GameInfo};
+
+#[test]
+fn dev_hooks_replace_the_servers() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::default();
+    let id = GameId(3);
+    let info = GameInfo {
+        id,
+        hooks: "def post_install(): pass".into(),
+        ..Default::default()
+    };
+    config.games().insert(
+        id,
+        Game {
+            info: info.clone(),
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+    let mut modified = BTreeMap::new();
+
+    // only used in developer mode
+    std::fs::write(dev_hooks_path(dir.path(), id), "def post_install(:").unwrap();
+    assert_eq!(hooks_source(&config, &info), info.hooks);
+
+    config.set_dev_hooks_dir(Some(dir.path().into()));
+    assert_eq!(hooks_source(&config, &info), "def post_install(:");
+    assert_eq!(
+        reload_changed_hooks(&config, dir.path(), &mut modified),
+        [id]
+    );
+    assert!(config.games().get(&id).unwrap().hooks_error.is_some());
+
+    // unchanged files aren't compiled again
+    assert!(reload_changed_hooks(&config, dir.path(), &mut modified).is_empty());
+
+    // a removed file means the server's hooks are used again
+    std::fs::remove_file(dev_hooks_path(dir.path(), id)).unwrap();
+    assert_eq!(
+        reload_changed_hooks(&config, dir.path(), &mut modified),
+        [id]
+    );
+    assert!(config.games().get(&id).unwrap().hooks_error.is_none());
+}
+
+#[test]
+fn setup_is_out_of_date_when_the_hooks_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::default();
+    config.set_games_dir(dir.path().join("games"));
+    let info = GameInfo {
+        id: GameId(3),
+        hooks: "def post_install(): pass".into(),
+        ..Default::default()
+    };
+    let game_dir = config.game_dir(info.id);
+    std::fs::create_dir_all(&game_dir).unwrap();
+    assert!(!setup_is_up_to_date(&config, &info));
+
+    let manifest = Manifest {
+        post_install_hooks: Some(hooks_hash(&config, &info)),
+        ..Default::default()
+    };
+    manifest.save(&game_dir).unwrap();
+    assert!(setup_is_up_to_date(&config, &info));
+
+    let hooks_dir = dir.path().join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    std::fs::write(dev_hooks_path(&hooks_dir, info.id), "def post_install(): 1").unwrap();
+    config.set_dev_hooks_dir(Some(hooks_dir));
+    assert!(!setup_is_up_to_date(&config, &info));
+}
@@ -0,0 +1,49 @@
+//! Checks finding the download URL in pages Google Drive shows instead of a file, using saved
+//! copies of them.
+
+use bramlett::sources::{parse_drive_download_link, parse_drive_download_url, LinkStrategy};
+use bramlett::ClientError;
+
+fn page(name: &str) -> String {
+    let path = format!(
+        "{}/tests/fixtures/drive/{name}.html",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn url_in_form_action() {
+    let url = parse_drive_download_url(&page("form_action")).unwrap();
+    assert_eq!(
+        url,
+        "https://drive.google.com/uc?export=download&id=1sBkd4vADCCH3WmoF6DqtKhjEt7G3D307&confirm=t"
+    );
+}
+
+#[test]
+fn url_in_hidden_inputs() {
+    let url = parse_drive_download_url(&page("hidden_inputs")).unwrap();
+    assert_eq!(
+        url,
+        "https://drive.usercontent.google.com/download?id=16CYi7pAMTsmbVmlEtUBOkLjvpqtQdght&export=download&confirm=t&uuid=6e1c8b3a-2f4d-4c1e-9a57-0b8f2d9e4a11"
+    );
+}
+
+#[test]
+fn error_page_is_bad_drive() {
+    let res = parse_drive_download_url(&page("quota_exceeded"));
+    assert!(matches!(res, Err(ClientError::BadDrive)), "{res:?}");
+}
+
+#[test]
+fn link_says_where_the_url_came_from() {
+    let link = parse_drive_download_link(&page("form_action")).unwrap();
+    assert_eq!(link.strategy, LinkStrategy::DriveFormAction);
+    let link = parse_drive_download_link(&page("hidden_inputs")).unwrap();
+    assert_eq!(link.strategy, LinkStrategy::DriveHiddenInputs);
+    assert_eq!(
+        link.url,
+        parse_drive_download_url(&page("hidden_inputs")).unwrap()
+    );
+}
@@ -0,0 +1,375 @@
+//! Checks archive password detection, extraction errors and install verification.
+
+use bramlett::download::{
+    detect_archive, detect_archive_file, extract_zip_with_password, find_password,
+    password_candidates, ArchiveKind,
+};
+use bramlett::manifest::{audit_install, verify_install, Manifest};
+use bramlett::ClientError;
+use common::GameInfo;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// The fixture's password is "game".
+const ARCHIVE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/game.7z");
+
+/// An archive without a password whose files have names that aren't safe to extract as they are:
+/// backslash separators, `?`, accents, a Windows device name and a `..` that leaves the game
+/// directory.
+const TRICKY_ARCHIVE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/tricky_names.7z"
+);
+
+#[test]
+fn finds_password_after_wrong_ones() {
+    let password = find_password(Path::new(ARCHIVE), ["nope", "also wrong", "game"]).unwrap();
+    assert_eq!(password, "game");
+}
+
+#[test]
+fn all_wrong_passwords_is_bad_zip_password() {
+    let res = find_password(Path::new(ARCHIVE), ["nope", "also wrong"]);
+    assert!(matches!(res, Err(ClientError::BadZipPassword)), "{res:?}");
+}
+
+#[test]
+fn game_password_is_tried_first() {
+    let info = GameInfo {
+        zip_password: Some("hunter2".into()),
+        ..Default::default()
+    };
+    let candidates = password_candidates(&info, None);
+    assert_eq!(candidates[0], "hunter2");
+    assert!(candidates.contains(&"game"));
+}
+
+#[test]
+fn default_password_goes_between_the_games_and_the_usual_ones() {
+    let info = GameInfo {
+        zip_password: Some("hunter2".into()),
+        ..Default::default()
+    };
+    let candidates = password_candidates(&info, Some("mirror"));
+    assert_eq!(candidates[..3], ["hunter2", "mirror", "game"]);
+
+    let info = GameInfo::default();
+    let candidates = password_candidates(&info, Some("mirror"));
+    assert_eq!(candidates[..2], ["mirror", "game"]);
+
+    // a default that's also a usual password isn't tried twice
+    let candidates = password_candidates(&info, Some("games"));
+    assert_eq!(candidates, ["games", "game", "password"]);
+}
+
+#[test]
+fn archive_kinds_are_detected() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let kind = |path: &str| detect_archive_file(&fixtures.join(path)).unwrap();
+    assert_eq!(kind("game.7z"), ArchiveKind::SevenZip);
+    assert_eq!(kind("formats/game.zip"), ArchiveKind::Zip);
+    assert_eq!(kind("formats/game.tar.gz"), ArchiveKind::TarGz);
+    assert_eq!(kind("formats/game.tar.xz"), ArchiveKind::TarXz);
+    assert_eq!(kind("drive/quota_exceeded.html"), ArchiveKind::Html);
+
+    assert_eq!(detect_archive(b"\n  <HTML><body>"), ArchiveKind::Html);
+    assert_eq!(detect_archive(b"not an archive"), ArchiveKind::Unknown);
+    assert_eq!(detect_archive(b""), ArchiveKind::Unknown);
+}
+
+#[test]
+fn every_archive_kind_is_extracted() {
+    let formats = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/formats");
+    for name in ["game.zip", "game.tar.gz", "game.tar.xz"] {
+        let archive = formats.join(name);
+        // they don't have passwords, so any works
+        assert_eq!(find_password(&archive, ["game"]).unwrap(), "game");
+
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, _rx) = watch::channel((0, 0));
+        extract_zip_with_password(
+            &archive,
+            dir.path(),
+            "game",
+            None,
+            false,
+            false,
+            tx,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        // the top-level `game` directory is removed like a 7zip file's
+        let readme = std::fs::read_to_string(dir.path().join("readme.txt")).unwrap();
+        assert!(!readme.is_empty(), "{name}");
+        let manifest = Manifest::load(dir.path()).unwrap();
+        assert_eq!(manifest.files.keys().collect::<Vec<_>>(), ["readme.txt"]);
+    }
+
+    let page = formats.join("../drive/quota_exceeded.html");
+    assert!(matches!(
+        find_password(&page, ["game"]),
+        Err(ClientError::WebPage)
+    ));
+    let unknown = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(unknown.path(), [0; 64]).unwrap();
+    assert!(matches!(
+        find_password(unknown.path(), ["game"]),
+        Err(ClientError::UnsupportedArchive(ArchiveKind::Unknown))
+    ));
+}
+
+#[test]
+fn empty_zip_files_keep_their_crc() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("game.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+    zip.start_file("empty.txt", zip::write::FileOptions::default())
+        .unwrap();
+    zip.start_file("readme.txt", zip::write::FileOptions::default())
+        .unwrap();
+    zip.write_all(b"hello").unwrap();
+    zip.finish().unwrap();
+
+    let dest = dir.path().join("game");
+    let (tx, _rx) = watch::channel((0, 0));
+    extract_zip_with_password(
+        &archive,
+        &dest,
+        "",
+        None,
+        true,
+        false,
+        tx,
+        &AtomicBool::new(false),
+    )
+    .unwrap();
+    let manifest = Manifest::load(&dest).unwrap();
+    assert_eq!(manifest.files["empty.txt"].crc, Some(0));
+    assert!(manifest.files["readme.txt"].crc.is_some_and(|crc| crc != 0));
+}
+
+#[test]
+fn missing_archive_is_not_a_file() {
+    let res = find_password(Path::new("does/not/exist.7z"), ["game"]);
+    assert!(
+        matches!(res, Err(ClientError::ArchiveNotAFile(_))),
+        "{res:?}"
+    );
+}
+
+#[test]
+fn canceled_extraction_stops() {
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("game");
+    let (tx, _rx) = watch::channel((0, 0));
+    let res = extract_zip_with_password(
+        Path::new(ARCHIVE),
+        &dest,
+        "game",
+        None,
+        false,
+        false,
+        tx,
+        &AtomicBool::new(true),
+    );
+    assert!(matches!(res, Err(ClientError::Canceled)), "{res:?}");
+    assert!(!dest.join("game.exe").exists());
+}
+
+#[test]
+fn strip_prefix_controls_nesting() {
+    let dir = tempfile::tempdir().unwrap();
+    let extract = |dest: &Path, strip_prefix| {
+        let (tx, _rx) = watch::channel((0, 0));
+        extract_zip_with_password(
+            Path::new(ARCHIVE),
+            dest,
+            "game",
+            strip_prefix,
+            false,
+            false,
+            tx,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+    };
+
+    // the fixture's files are all in one top-level directory, which is removed by default
+    let auto = dir.path().join("auto");
+    extract(&auto, None);
+    assert!(auto.join("game.exe").is_file());
+
+    let kept = dir.path().join("kept");
+    extract(&kept, Some(0));
+    let nested = std::fs::read_dir(&kept)
+        .unwrap()
+        .flatten()
+        .find(|e| e.path().is_dir())
+        .unwrap();
+    assert!(nested.path().join("game.exe").is_file());
+}
+
+#[test]
+fn progress_can_be_reported_to_a_closure() {
+    let dir = tempfile::tempdir().unwrap();
+    let reports = Mutex::new(Vec::new());
+    extract_zip_with_password(
+        Path::new(ARCHIVE),
+        &dir.path().join("game"),
+        "game",
+        None,
+        false,
+        false,
+        |done, total| reports.lock().unwrap().push((done, total)),
+        &AtomicBool::new(false),
+    )
+    .unwrap();
+
+    let reports = reports.into_inner().unwrap();
+    assert!(!reports.is_empty());
+    assert!(reports
+        .iter()
+        .all(|&(done, total)| total > 0 && done <= total));
+    assert!(reports.windows(2).all(|w| w[0].0 <= w[1].0));
+}
+
+#[test]
+fn unsafe_names_are_made_safe_or_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("game");
+    let (tx, _rx) = watch::channel((0, 0));
+    extract_zip_with_password(
+        Path::new(TRICKY_ARCHIVE),
+        &dest,
+        "",
+        None,
+        false,
+        false,
+        tx,
+        &AtomicBool::new(false),
+    )
+    .unwrap();
+
+    assert!(dest.join("game.exe").is_file());
+    assert!(dest.join("data").join("level.txt").is_file());
+    assert!(dest.join("what_.txt").is_file());
+    assert!(dest.join("caf\u{e9} \u{fc}n\u{ef}code.txt").is_file());
+    assert!(dest.join("_CON.txt").is_file());
+    assert!(!dir.path().join("escaped.txt").exists());
+    assert!(!dir.path().parent().unwrap().join("escaped.txt").exists());
+}
+
+#[test]
+fn missing_files_fail_verification() {
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("game");
+    let (tx, _rx) = watch::channel((0, 0));
+    extract_zip_with_password(
+        Path::new(ARCHIVE),
+        &dest,
+        "game",
+        None,
+        false,
+        false,
+        tx,
+        &AtomicBool::new(false),
+    )
+    .unwrap();
+    verify_install(&dest, "game.exe").unwrap();
+
+    std::fs::remove_file(dest.join("data").join("level.txt")).unwrap();
+    let res = verify_install(&dest, "game.exe");
+    assert!(
+        matches!(&res, Err(ClientError::BrokenInstall(e)) if e.contains("data/level.txt")),
+        "{res:?}"
+    );
+
+    std::fs::remove_file(dest.join("game.exe")).unwrap();
+    let res = verify_install(&dest, "game.exe");
+    assert!(
+        matches!(&res, Err(ClientError::BrokenInstall(e)) if e.contains("game.exe")),
+        "{res:?}"
+    );
+}
+
+#[test]
+fn audit_lists_every_damaged_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let dest = dir.path().join("game");
+    let (tx, _rx) = watch::channel((0, 0));
+    extract_zip_with_password(
+        Path::new(ARCHIVE),
+        &dest,
+        "game",
+        None,
+        false,
+        true,
+        tx,
+        &AtomicBool::new(false),
+    )
+    .unwrap();
+    let audit = audit_install(&dest, "game.exe");
+    assert!(!audit.needs_repair(), "{audit:?}");
+    let size: u64 = Manifest::load(&dest)
+        .unwrap()
+        .files
+        .values()
+        .map(|e| e.size)
+        .sum();
+    assert_eq!(audit.size, size);
+
+    std::fs::remove_file(dest.join("game.exe")).unwrap();
+    std::fs::write(dest.join("data").join("level.txt"), "tampered").unwrap();
+    let audit = audit_install(&dest, "game.exe");
+    assert_eq!(audit.damaged, ["game.exe", "data/level.txt"]);
+
+    // a damaged executable is found by its hash like any other file
+    std::fs::write(dest.join("game.exe"), "corrupted").unwrap();
+    let audit = audit_install(&dest, "game.exe");
+    assert_eq!(audit.damaged, ["data/level.txt", "game.exe"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn no_space_left_is_disk_full() {
+    let enospc = std::io::Error::from_raw_os_error(28);
+    assert!(matches!(ClientError::from(enospc), ClientError::DiskFull));
+}
+
+#[test]
+fn hashes_are_recorded_when_asked() {
+    let dir = tempfile::tempdir().unwrap();
+    let extract = |dest: &Path, hash_files| {
+        let (tx, _rx) = watch::channel((0, 0));
+        extract_zip_with_password(
+            Path::new(ARCHIVE),
+            dest,
+            "game",
+            None,
+            true,
+            hash_files,
+            tx,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        Manifest::load(dest).unwrap()
+    };
+
+    let plain = extract(&dir.path().join("plain"), false);
+    assert!(plain.files.values().all(|e| e.sha256.is_none()));
+
+    let dest = dir.path().join("hashed");
+    let hashed = extract(&dest, true);
+    assert!(!hashed.files.is_empty());
+    for (key, entry) in &hashed.files {
+        let expected: [u8; 32] = Sha256::digest(std::fs::read(dest.join(key)).unwrap()).into();
+        assert_eq!(entry.sha256, Some(expected), "{key}");
+    }
+
+    // files that didn't change keep their hashes when extracting again
+    assert_eq!(extract(&dest, true), hashed);
+}
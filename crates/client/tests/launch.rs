@@ -0,0 +1,168 @@
+//! Checks which executables games are allowed to run, what they can use while running, the
+//! compatibility settings they're run with, noticing when an antivirus has removed them, and which
+//! homepages they can open.
+
+use bramlett::sandbox::{self, Limits};
+use bramlett::{antivirus, compat, web_page, ClientError, Config};
+use common::{Compat, DpiScaling, GameId, GameInfo};
+
+fn info(exe: &str) -> GameInfo {
+    GameInfo {
+        id: GameId(1),
+        exe: exe.into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn exes_outside_the_game_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::default();
+    config.set_games_dir(dir.path().to_path_buf());
+    let game_dir = config.game_dir(GameId(1));
+    std::fs::create_dir_all(game_dir.join("bin")).unwrap();
+    std::fs::write(game_dir.join("bin/game.exe"), "").unwrap();
+    std::fs::write(dir.path().join("evil.exe"), "").unwrap();
+
+    let inside = config.launch(&info("bin/game.exe"));
+    config.check_launch(GameId(1), &inside).unwrap();
+    let outside = config.launch(&info("../evil.exe"));
+    let res = config.check_launch(GameId(1), &outside);
+    assert!(
+        matches!(res, Err(ClientError::ExeOutsideGame(_))),
+        "{res:?}"
+    );
+
+    config.set_restrict_exes(false);
+    config.check_launch(GameId(1), &outside).unwrap();
+}
+
+#[test]
+fn only_allowed_extensions_are_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::default();
+    config.set_games_dir(dir.path().to_path_buf());
+    let game_dir = config.game_dir(GameId(1));
+    std::fs::create_dir_all(&game_dir).unwrap();
+    std::fs::write(game_dir.join("game.EXE"), "").unwrap();
+    std::fs::write(game_dir.join("setup.bat"), "").unwrap();
+
+    config.set_allowed_exe_extensions(Some(vec![".exe".into()]));
+    let game = config.launch(&info("game.EXE"));
+    config.check_launch(GameId(1), &game).unwrap();
+    let script = config.launch(&info("setup.bat"));
+    let res = config.check_launch(GameId(1), &script);
+    assert!(matches!(res, Err(ClientError::ExeNotAllowed(_))), "{res:?}");
+}
+
+#[tokio::test]
+async fn games_use_their_own_limits_or_everyones() {
+    let config = Config::default();
+    assert!(config.sandbox(GameId(1)).is_unlimited());
+
+    // limits left out of the config file are unlimited
+    let everyone: Limits = serde_json::from_str(r#"{"memory": 2000000000}"#).unwrap();
+    assert_eq!(everyone.processes, None);
+    config.set_sandbox(everyone);
+    let own = Limits {
+        processes: Some(4),
+        ..Limits::default()
+    };
+    config.set_game_sandbox(GameId(2), Some(own));
+    assert_eq!(config.sandbox(GameId(1)), everyone);
+    assert_eq!(config.sandbox(GameId(2)), own);
+    config.set_game_sandbox(GameId(2), None);
+    assert_eq!(config.sandbox(GameId(2)), everyone);
+
+    // job objects are only on Windows, so there's nothing to put the game in elsewhere
+    #[cfg(unix)]
+    {
+        assert!(sandbox::job(&everyone).unwrap().is_none());
+        let mut command = tokio::process::Command::new("true");
+        let mut child = sandbox::spawn(&mut command, 0, None).unwrap();
+        assert!(child.wait().await.unwrap().success());
+    }
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn games_are_started_in_their_job() {
+    let limits = Limits {
+        processes: Some(4),
+        ..Limits::default()
+    };
+    let job = sandbox::job(&limits).unwrap().unwrap();
+    let mut command = tokio::process::Command::new("ping");
+    command
+        .args(["-n", "2", "127.0.0.1"])
+        .stdout(std::process::Stdio::null());
+    let mut child = sandbox::spawn(&mut command, 0, Some(&job)).unwrap();
+    assert!(job.process_ids().unwrap().contains(&child.id().unwrap()));
+    // it was resumed once it was in the job
+    assert!(child.wait().await.unwrap().success());
+}
+
+#[test]
+fn compat_settings_become_layers() {
+    // games without any are left alone
+    let info: GameInfo = serde_json::from_str(
+        r#"{"name": "Old", "id": 1, "icon": "", "gdrive_id": "", "exe": "old.exe", "hooks": ""}"#,
+    )
+    .unwrap();
+    assert_eq!(info.compat, Compat::default());
+    assert_eq!(compat::layers(&info.compat), None);
+
+    let old: Compat = serde_json::from_str(
+        r#"{"disable_fullscreen_optimizations": true, "dpi_scaling": "system_enhanced"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        compat::layers(&old).as_deref(),
+        Some("~ DISABLEDXMAXIMIZEDWINDOWEDMODE GDIDPISCALING DPIUNAWARE")
+    );
+    let sharp = Compat {
+        dpi_scaling: Some(DpiScaling::Application),
+        ..Compat::default()
+    };
+    assert_eq!(compat::layers(&sharp).as_deref(), Some("~ HIGHDPIAWARE"));
+}
+
+#[test]
+fn missing_exes_are_reported_as_quarantined() {
+    let dir = tempfile::tempdir().unwrap();
+    let exe = dir.path().join("game.exe");
+    std::fs::write(&exe, "").unwrap();
+    antivirus::check_exe(&exe).unwrap();
+
+    // like Windows Defender taking it away right after it was extracted
+    std::fs::remove_file(&exe).unwrap();
+    let res = antivirus::check_exe(&exe);
+    assert!(
+        matches!(&res, Err(ClientError::ExeQuarantined(path)) if *path == exe),
+        "{res:?}"
+    );
+    assert!(res.unwrap_err().to_string().contains("antivirus"));
+}
+
+#[test]
+fn only_web_pages_are_opened_as_homepages() {
+    for url in [
+        "https://example.com/controls",
+        "http://example.com",
+        " https://example.com ",
+    ] {
+        web_page(url).unwrap();
+    }
+    for url in [
+        "file:///C:/Windows/System32/calc.exe",
+        "javascript:alert(1)",
+        "example.com",
+        "",
+    ] {
+        let res = web_page(url);
+        assert!(
+            matches!(res, Err(ClientError::NotAWebPage(_))),
+            "{url}: {res:?}"
+        );
+    }
+}
@@ -0,0 +1,1137 @@
+//! Runs the game list and install pipeline against a local mock of the games server.
+
+use bramlett::manifest::Manifest;
+use bramlett::schedule::{self, DownloadWindow, TimeOfDay};
+use bramlett::sources::{Direct, DownloadSource, LinkStrategy};
+use bramlett::{
+    refresh_delay, update_game_list, ClientError, Config, Ctx, ErrorStage, Game, GameStatus,
+    PartialDownload, Work, ERROR_HISTORY_LEN, MAX_REFRESH_DELAY,
+};
+use common::{GameId, GameInfo, GameSource};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{net::SocketAddr, path::PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{mpsc, watch};
+use warp::{Filter, Reply};
+
+/// A password protected 7z archive with a single `Fixture Game` root directory.
+const ARCHIVE: &[u8] = include_bytes!("fixtures/game.7z");
+
+fn game_info(id: i32, name: &str) -> GameInfo {
+    GameInfo {
+        name: name.into(),
+        id: GameId(id),
+        icon: String::new(),
+        gdrive_id: format!("fixture-{id}"),
+        exe: "game.exe".into(),
+        ..Default::default()
+    }
+}
+
+/// A tar.gz archive with a single `game` root directory, holding `readme.txt`.
+const TAR_ARCHIVE: &[u8] = include_bytes!("fixtures/formats/game.tar.gz");
+
+/// Serves `games` at `/games` and the fixture archive at `/uc`, like the games server and Google
+/// Drive do.
+fn serve(games: Vec<GameInfo>) -> SocketAddr {
+    serve_archive(games, ARCHIVE)
+}
+
+/// [`serve`], with `archive` instead of the fixture archive.
+fn serve_archive(games: Vec<GameInfo>, archive: &'static [u8]) -> SocketAddr {
+    let games = warp::path("games")
+        .and(warp::path::end())
+        .map(move || warp::reply::json(&games));
+    let archive = warp::path("uc").map(move || archive.to_vec());
+
+    let (addr, server) = warp::serve(games.or(archive)).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    addr
+}
+
+/// Serves the fixture archive for any request, but cuts off the first `truncated` responses
+/// halfway through.
+async fn serve_truncated(truncated: usize) -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let served = Arc::new(AtomicUsize::new(0));
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let served = served.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let body = if served.fetch_add(1, Ordering::SeqCst) < truncated {
+                    &ARCHIVE[..ARCHIVE.len() / 2]
+                } else {
+                    ARCHIVE
+                };
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    ARCHIVE.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(body).await.unwrap();
+                stream.shutdown().await.unwrap();
+            });
+        }
+    });
+    addr
+}
+
+/// Creates a config pointing at the mock server, with all of its files in a temporary directory.
+fn config(addr: SocketAddr, dir: &tempfile::TempDir) -> Config {
+    // keep the tests from touching the real config file
+    std::env::set_var(
+        "BRAMLETT_CONFIG_DIR",
+        std::env::temp_dir().join("bramlett-tests"),
+    );
+
+    let config = Config::default();
+    config.set_games_dir(dir.path().join("games"));
+    config.set_saves_dir(dir.path().join("saves"));
+    config.set_server_url(Some(format!("http://{addr}")));
+    config.set_drive_url(Some(format!("http://{addr}")));
+    config
+}
+
+fn name(config: &Config, id: i32) -> Option<String> {
+    config.games().get(&GameId(id)).map(|g| g.info.name.clone())
+}
+
+#[tokio::test]
+async fn update_game_list_adds_updates_and_prunes() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(
+        serve(vec![
+            game_info(0, "Zero"),
+            game_info(1, "One"),
+            game_info(2, "Two"),
+        ]),
+        &dir,
+    );
+
+    assert_eq!(update_game_list(&config, false).await.unwrap(), 3);
+    assert_eq!(config.games().len(), 3);
+    assert!(config
+        .games()
+        .iter()
+        .all(|g| matches!(g.status, GameStatus::NotDownloaded)));
+
+    config.games().get_mut(&GameId(1)).unwrap().status = GameStatus::Ready;
+
+    let addr = serve(vec![game_info(0, "Zero v2"), game_info(3, "Three")]);
+    config.set_server_url(Some(format!("http://{addr}")));
+
+    assert_eq!(update_game_list(&config, false).await.unwrap(), 1);
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero"));
+    assert_eq!(name(&config, 1).as_deref(), Some("One")); // installed, so it's kept
+    assert_eq!(name(&config, 2), None);
+    assert_eq!(name(&config, 3).as_deref(), Some("Three"));
+
+    assert_eq!(update_game_list(&config, true).await.unwrap(), 0);
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero v2"));
+    assert!(matches!(
+        config.games().get(&GameId(1)).unwrap().status,
+        GameStatus::Ready
+    ));
+}
+
+#[tokio::test]
+async fn unreadable_games_are_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero"), game_info(1, "One")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let mut one = serde_json::to_value(game_info(1, "One v2")).unwrap();
+    one.as_object_mut().unwrap().remove("exe");
+    let list = serde_json::json!([game_info(0, "Zero v2"), one, 42, game_info(2, "Two")]);
+    let games = warp::path("games").map(move || warp::reply::json(&list));
+    let (addr, server) = warp::serve(games).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    config.set_server_url(Some(format!("http://{addr}")));
+
+    assert_eq!(update_game_list(&config, true).await.unwrap(), 1);
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero v2"));
+    // it's still on the server, so it's kept as it was
+    assert_eq!(name(&config, 1).as_deref(), Some("One"));
+    assert_eq!(name(&config, 2).as_deref(), Some("Two"));
+
+    let skipped = config.skipped_games();
+    assert_eq!(skipped.len(), 2);
+    assert!(skipped[0].starts_with("One v2 (ID 1): missing field `exe`"));
+    assert!(skipped[1].starts_with("game #3: "));
+}
+
+#[tokio::test]
+async fn pinned_games_keep_their_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+    config.set_pinned(GameId(0), true);
+
+    let addr = serve(vec![game_info(0, "Zero v2")]);
+    config.set_server_url(Some(format!("http://{addr}")));
+    update_game_list(&config, true).await.unwrap();
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero"));
+    assert_eq!(
+        config.held_update(GameId(0)).map(|info| info.name),
+        Some("Zero v2".into())
+    );
+
+    assert!(config.apply_held_update(GameId(0)));
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero v2"));
+    assert!(config.is_pinned(GameId(0)));
+    assert!(!config.apply_held_update(GameId(0)));
+
+    let addr = serve(vec![game_info(0, "Zero v3")]);
+    config.set_server_url(Some(format!("http://{addr}")));
+    update_game_list(&config, true).await.unwrap();
+    config.set_pinned(GameId(0), false);
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero v3"));
+    assert_eq!(config.held_update(GameId(0)), None);
+}
+
+#[tokio::test]
+async fn unchanged_game_list_isnt_downloaded_again() {
+    let full_responses = Arc::new(AtomicUsize::new(0));
+    let games = warp::path("games")
+        .and(warp::header::optional::<String>("if-none-match"))
+        .map({
+            let full_responses = full_responses.clone();
+            move |etag: Option<String>| {
+                if etag.as_deref() == Some("\"v1\"") {
+                    return warp::http::StatusCode::NOT_MODIFIED.into_response();
+                }
+                full_responses.fetch_add(1, Ordering::SeqCst);
+                let list = warp::reply::json(&vec![game_info(0, "Zero")]);
+                warp::reply::with_header(list, "etag", "\"v1\"").into_response()
+            }
+        });
+    let (addr, server) = warp::serve(games).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(addr, &dir);
+    update_game_list(&config, true).await.unwrap();
+    update_game_list(&config, true).await.unwrap();
+    assert_eq!(full_responses.load(Ordering::SeqCst), 1);
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero"));
+
+    // there's nothing to keep once the list is empty, so it's downloaded again
+    config.games().clear();
+    update_game_list(&config, true).await.unwrap();
+    assert_eq!(full_responses.load(Ordering::SeqCst), 2);
+    assert_eq!(name(&config, 0).as_deref(), Some("Zero"));
+}
+
+#[tokio::test]
+async fn failed_refresh_backs_off() {
+    let interval = Duration::from_secs(60);
+    assert_eq!(refresh_delay(interval, 0), interval);
+    assert_eq!(refresh_delay(interval, 3), interval * 8);
+    assert_eq!(refresh_delay(interval, 100), MAX_REFRESH_DELAY);
+
+    let dir = tempfile::tempdir().unwrap();
+    // nothing listens on the discard port
+    let config = config(SocketAddr::from(([127, 0, 0, 1], 9)), &dir);
+    assert!(update_game_list(&config, true).await.is_err());
+    assert!(config.game_list_updated().is_none());
+
+    let addr = serve(vec![game_info(0, "Zero")]);
+    config.set_server_url(Some(format!("http://{addr}")));
+    update_game_list(&config, true).await.unwrap();
+    assert!(config.game_list_updated().is_some());
+}
+
+#[tokio::test]
+async fn install_downloads_and_extracts() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+
+    let mut events = config.status_events();
+    let mut outcomes = config.install_outcomes();
+    let games = config.games();
+    let (tx, rx) = watch::channel((0, 0));
+    let game = {
+        let mut game = games.get_mut(&GameId(0)).unwrap();
+        config.set_status(&mut game, GameStatus::Downloading(rx.clone()));
+        game.clone()
+    };
+
+    let outcome = bramlett::download::install_game(&ctx, game, tx)
+        .await
+        .unwrap();
+    assert_eq!(outcome.game_id, GameId(0));
+    assert_eq!(outcome.install_dir, config.game_dir(GameId(0)));
+    assert_eq!(outcome.bytes_downloaded, ARCHIVE.len() as u64);
+    assert_eq!(outcomes.try_recv().unwrap(), outcome);
+    assert_eq!(ctx.stats.bytes_downloaded(), ARCHIVE.len() as u64);
+    assert_eq!(ctx.stats.installs_completed(), 1);
+    assert_eq!(ctx.stats.installs_failed(), 0);
+
+    assert!(matches!(
+        games.get(&GameId(0)).unwrap().status,
+        GameStatus::Ready
+    ));
+    assert_eq!(*rx.borrow(), (ARCHIVE.len() as u64, ARCHIVE.len() as u64));
+
+    let mut statuses = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        assert_eq!(event.id, GameId(0));
+        statuses.push(event.new.unwrap());
+    }
+    assert!(matches!(
+        statuses[..],
+        [
+            GameStatus::Downloading(_),
+            GameStatus::Installing(_),
+            GameStatus::Ready
+        ]
+    ));
+
+    let game_dir: PathBuf = config.game_dir(GameId(0));
+    assert_eq!(
+        std::fs::read_to_string(game_dir.join("game.exe")).unwrap(),
+        "#!/bin/sh\necho fixture\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(game_dir.join("data").join("level.txt")).unwrap(),
+        "level 1\n"
+    );
+}
+
+#[tokio::test]
+async fn tar_archives_are_extracted_as_they_download() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve_archive(vec![game_info(0, "Zero")], TAR_ARCHIVE), &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+
+    let mut events = config.status_events();
+    let games = config.games();
+    let (tx, rx) = watch::channel((0, 0));
+    let game = {
+        let mut game = games.get_mut(&GameId(0)).unwrap();
+        config.set_status(&mut game, GameStatus::Downloading(rx.clone()));
+        game.clone()
+    };
+
+    let outcome = bramlett::download::install_game(&ctx, game, tx)
+        .await
+        .unwrap();
+    let len = TAR_ARCHIVE.len() as u64;
+    assert_eq!(outcome.bytes_downloaded, len);
+    assert_eq!(ctx.stats.bytes_downloaded(), len);
+    assert_eq!(*rx.borrow(), (len, len));
+
+    let mut statuses = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        statuses.push(event.new.unwrap());
+    }
+    // it was never a file to extract
+    assert!(matches!(
+        statuses[..],
+        [GameStatus::Downloading(_), GameStatus::Ready]
+    ));
+    assert!(!config.archive_path(GameId(0)).exists());
+
+    let game_dir = config.game_dir(GameId(0));
+    assert_eq!(
+        std::fs::read_to_string(game_dir.join("readme.txt")).unwrap(),
+        "hello\n"
+    );
+    assert!(!game_dir.with_extension("extracting").exists());
+    let manifest = Manifest::load(&game_dir).unwrap();
+    assert_eq!(manifest.files.keys().collect::<Vec<_>>(), ["readme.txt"]);
+}
+
+#[tokio::test]
+async fn games_stay_installing_until_their_setup_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let (py_tx, mut py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let python = tokio::spawn({
+        let games = games.clone();
+        async move {
+            let request: bramlett::py::Request = py_rx.recv().await.unwrap();
+            assert_eq!(request.ty, bramlett::py::RequestTy::PostInstall);
+            // it can't be run or deleted yet
+            assert!(matches!(
+                games.get(&GameId(0)).unwrap().status,
+                GameStatus::Installing(_)
+            ));
+            let _ = request.finish.send(Ok(()));
+        }
+    });
+    let (tx, rx) = watch::channel((0, 0));
+    let game = {
+        let mut game = games.get_mut(&GameId(0)).unwrap();
+        config.set_status(&mut game, GameStatus::Downloading(rx));
+        game.clone()
+    };
+
+    bramlett::download::install_game_and_set_up(&ctx, game, tx)
+        .await
+        .unwrap();
+    python.await.unwrap();
+    assert!(matches!(
+        games.get(&GameId(0)).unwrap().status,
+        GameStatus::Ready
+    ));
+}
+
+#[tokio::test]
+async fn kept_archive_is_reinstalled_without_downloading() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+    config.set_keep_archives(true);
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let install = || {
+        let (tx, rx) = watch::channel((0, 0));
+        let mut game = games.get_mut(&GameId(0)).unwrap();
+        config.set_status(&mut game, GameStatus::Downloading(rx));
+        bramlett::download::install_game(&ctx, game.clone(), tx)
+    };
+
+    install().await.unwrap();
+    let info = games.get(&GameId(0)).unwrap().info.clone();
+    assert!(config.cached_archive(&info).is_some());
+    assert!(!config.archive_path(GameId(0)).exists());
+
+    // nothing listens on the discard port, so this only works if the cache is used
+    config.set_drive_url(Some("http://127.0.0.1:9".into()));
+    std::fs::remove_dir_all(config.game_dir(GameId(0))).unwrap();
+    let outcome = install().await.unwrap();
+    assert_eq!(outcome.bytes_downloaded, 0);
+    assert!(config.game_dir(GameId(0)).join("game.exe").is_file());
+
+    // a new version of the game isn't installed from the old archive
+    let mut updated = info.clone();
+    updated.changelog = Some("new levels".into());
+    assert!(config.cached_archive(&updated).is_none());
+
+    assert_eq!(config.clear_archive_cache().unwrap(), ARCHIVE.len() as u64);
+    assert!(config.cached_archive(&info).is_none());
+    assert!(!config.archive_cache_dir().exists());
+}
+
+#[test]
+fn archive_cache_evicts_least_recently_used() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(SocketAddr::from(([127, 0, 0, 1], 9)), &dir);
+    let size = ARCHIVE.len() as u64;
+    config.set_archive_cache_limit(Some(2 * size));
+    let cache = |id| {
+        let info = game_info(id, "Cached");
+        let archive = dir.path().join(format!("{id}.7z"));
+        std::fs::write(&archive, ARCHIVE).unwrap();
+        config.cache_archive(&info, &archive).unwrap();
+        info
+    };
+
+    let first = cache(0);
+    let second = cache(1);
+    config.touch_cached_archive(GameId(0));
+    let third = cache(2);
+    assert!(config.cached_archive(&first).is_some());
+    assert!(config.cached_archive(&second).is_none());
+    assert!(config.cached_archive(&third).is_some());
+
+    // lowering the limit evicts down to it
+    config.set_archive_cache_limit(Some(size));
+    assert!(config.cached_archive(&first).is_none());
+    assert!(config.cached_archive(&third).is_some());
+    assert_eq!(config.archive_cache_size(), size);
+}
+
+#[tokio::test]
+async fn unreachable_proxy_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+
+    assert!(matches!(
+        config.set_proxy(Some("not a url".into())),
+        Err(ClientError::InvalidProxy(_))
+    ));
+
+    // nothing listens on the discard port
+    config.set_proxy(Some("http://127.0.0.1:9".into())).unwrap();
+    let res = update_game_list(&config, true).await;
+    assert!(
+        matches!(res, Err(ClientError::ProxyUnreachable(_))),
+        "{res:?}"
+    );
+
+    config.set_proxy(None).unwrap();
+    update_game_list(&config, true).await.unwrap();
+}
+
+#[tokio::test]
+async fn truncated_download_is_retried() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve_truncated(2).await, &dir);
+    config.games().insert(
+        GameId(0),
+        Game {
+            info: game_info(0, "Zero"),
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let install = || {
+        let (tx, rx) = watch::channel((0, 0));
+        let game = {
+            let mut game = games.get_mut(&GameId(0)).unwrap();
+            config.set_status(&mut game, GameStatus::Downloading(rx));
+            game.clone()
+        };
+        bramlett::download::install_game(&ctx, game, tx)
+    };
+
+    config.set_download_retries(0).unwrap();
+    let res = install().await;
+    assert!(
+        matches!(
+            res,
+            Err(ClientError::DownloadIncomplete { got, expected })
+                if got == ARCHIVE.len() as u64 / 2 && expected == ARCHIVE.len() as u64
+        ),
+        "{res:?}"
+    );
+    let status = games.get(&GameId(0)).unwrap().status.clone();
+    assert!(
+        matches!(&status, GameStatus::Failed(e) if e.starts_with("download incomplete")),
+        "{status:?}"
+    );
+    // failures are saved as not downloaded
+    assert_eq!(serde_json::to_value(&status).unwrap(), "NotDownloaded");
+
+    // the second response is cut off too, but the retry after it gets the whole archive
+    config.set_download_retries(1).unwrap();
+    install().await.unwrap();
+    assert!(matches!(
+        games.get(&GameId(0)).unwrap().status,
+        GameStatus::Ready
+    ));
+    assert!(config.game_dir(GameId(0)).join("game.exe").is_file());
+}
+
+/// Serves the fixture archive, but answers any request for a range with part of a bigger file,
+/// like a server that ignores `If-Range` after the archive changed.
+async fn serve_other_ranges() -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_ascii_lowercase();
+                let (head, body) = if request.contains("\r\nrange:") {
+                    (
+                        "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 10-19/5000\r\n"
+                            .to_owned(),
+                        &[0; 10][..],
+                    )
+                } else {
+                    ("HTTP/1.1 200 OK\r\n".to_owned(), ARCHIVE)
+                };
+                let head = format!(
+                    "{head}Content-Type: application/octet-stream\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(body).await.unwrap();
+                stream.shutdown().await.unwrap();
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn range_of_a_changed_archive_isnt_added_to_the_old_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = serve_other_ranges().await;
+    let config = config(addr, &dir);
+    let id = GameId(0);
+    let path = config.archive_path(id).with_extension("7z.part");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, [1; 10]).unwrap();
+    let game = Game {
+        info: game_info(0, "Zero"),
+        status: GameStatus::NotDownloaded,
+        partial: Some(PartialDownload {
+            path,
+            offset: 10,
+            content_length: Some(1000),
+            etag: Some("\"old\"".into()),
+            last_modified: None,
+        }),
+        hooks_error: None,
+    };
+    config.games().insert(id, game.clone());
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let (tx, _rx) = watch::channel((0, 0));
+    let source = Direct(format!("http://{addr}/archive"));
+    let path = bramlett::download::download_game(game, ctx, &source, &tx)
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read(path).unwrap(), ARCHIVE);
+}
+
+#[tokio::test]
+async fn downloads_go_to_the_staging_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve_truncated(2).await, &dir);
+    let staging_dir = dir.path().join("staging");
+    config.set_staging_dir(Some(staging_dir.clone()));
+    config.set_keep_archives(true);
+    config.games().insert(
+        GameId(0),
+        Game {
+            info: game_info(0, "Zero"),
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let install = || {
+        let (tx, rx) = watch::channel((0, 0));
+        let game = {
+            let mut game = games.get_mut(&GameId(0)).unwrap();
+            config.set_status(&mut game, GameStatus::Downloading(rx));
+            game.clone()
+        };
+        bramlett::download::install_game(&ctx, game, tx)
+    };
+
+    config.set_download_retries(0).unwrap();
+    install().await.unwrap_err();
+    let partial = games.get(&GameId(0)).unwrap().partial.clone().unwrap();
+    assert_eq!(partial.path, staging_dir.join("0.7z.part"));
+    assert!(partial.path.is_file());
+    // kept to be resumed
+    assert!(bramlett::cleanup::find_orphans(&config).is_empty());
+
+    config.set_download_retries(1).unwrap();
+    let outcome = install().await.unwrap();
+    assert!(config.game_dir(GameId(0)).join("game.exe").is_file());
+    assert!(!staging_dir.join("0.7z").exists());
+    // the mock can't resume, so the whole archive was downloaded again
+    assert!(partial.offset > 0);
+    assert_eq!(outcome.bytes_downloaded, ARCHIVE.len() as u64);
+    assert!(ctx.resumed_from.is_empty());
+    let info = games.get(&GameId(0)).unwrap().info.clone();
+    assert!(config.cached_archive(&info).is_some());
+}
+
+#[tokio::test]
+async fn links_are_resolved_without_downloading() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = serve(Vec::new());
+    let config = config(addr, &dir);
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config, reqwest::Client::new(), py_tx);
+
+    // the mock sends the file straight away, like Drive does for small files
+    let source = GameSource::GoogleDrive("fixture-0".into());
+    let link = bramlett::sources::resolve_link(&ctx, &source)
+        .await
+        .unwrap();
+    assert_eq!(link.strategy, LinkStrategy::DriveUnconfirmed);
+    assert_eq!(
+        link.url,
+        format!("http://{addr}/uc?export=download&id=fixture-0")
+    );
+
+    let source = GameSource::Direct("http://example.invalid/game.7z".into());
+    let link = bramlett::sources::resolve_link(&ctx, &source)
+        .await
+        .unwrap();
+    assert_eq!(link.strategy, LinkStrategy::Direct);
+    assert_eq!(link.url, "http://example.invalid/game.7z");
+}
+
+#[tokio::test]
+async fn download_sizes_are_estimated_without_downloading() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = serve(Vec::new());
+    let config = config(addr, &dir);
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    assert!(config.set_preflight_concurrency(0).is_err());
+    config.set_preflight_concurrency(2).unwrap();
+
+    let closed = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let unreachable = GameInfo {
+        gdrive_id: String::new(),
+        mirrors: vec![GameSource::Direct(format!("http://{closed}/game.7z"))],
+        ..game_info(1, "Unreachable")
+    };
+    let games = vec![game_info(0, "Zero"), unreachable, game_info(2, "Two")];
+    let estimate = bramlett::download::estimate_downloads(&ctx, games).await;
+    assert_eq!(
+        estimate.sizes.into_iter().collect::<Vec<_>>(),
+        [
+            (GameId(0), ARCHIVE.len() as u64),
+            (GameId(2), ARCHIVE.len() as u64)
+        ]
+    );
+    assert_eq!(estimate.unknown, [GameId(1)]);
+    assert!(!config.archive_path(GameId(0)).exists());
+}
+
+#[tokio::test]
+async fn empty_download_is_reported_and_not_kept() {
+    let empty = warp::path("uc").map(Vec::<u8>::new);
+    let (addr, server) = warp::serve(empty).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(addr, &dir);
+    config.set_download_retries(1).unwrap();
+    config.games().insert(
+        GameId(0),
+        Game {
+            info: game_info(0, "Zero"),
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let (tx, rx) = watch::channel((0, 0));
+    let game = {
+        let mut game = games.get_mut(&GameId(0)).unwrap();
+        config.set_status(&mut game, GameStatus::Downloading(rx));
+        game.clone()
+    };
+    let res = bramlett::download::install_game(&ctx, game, tx).await;
+    assert!(matches!(res, Err(ClientError::EmptyArchive(0))), "{res:?}");
+    assert_eq!(ctx.stats.installs_failed(), 1);
+    assert!(matches!(
+        &games.get(&GameId(0)).unwrap().status,
+        GameStatus::Failed(e) if e.contains("empty")
+    ));
+    let errors = config.error_history(GameId(0));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].stage, ErrorStage::Download);
+    assert!(errors[0].message.contains("empty"));
+    assert!(games.get(&GameId(0)).unwrap().partial.is_none());
+    assert!(!config.archive_path(GameId(0)).exists());
+    assert!(!config
+        .archive_path(GameId(0))
+        .with_extension("7z.part")
+        .exists());
+}
+
+#[tokio::test]
+async fn failing_source_falls_back_to_mirror() {
+    let dir = tempfile::tempdir().unwrap();
+    // google drive always cuts the download off
+    let config = config(serve_truncated(usize::MAX).await, &dir);
+    config.set_download_retries(0).unwrap();
+    let mirror = serve(Vec::new());
+    config.games().insert(
+        GameId(0),
+        Game {
+            info: GameInfo {
+                mirrors: vec![GameSource::Direct(format!("http://{mirror}/uc"))],
+                ..game_info(0, "Zero")
+            },
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let (tx, rx) = watch::channel((0, 0));
+    let game = {
+        let games = config.games();
+        let mut game = games.get_mut(&GameId(0)).unwrap();
+        config.set_status(&mut game, GameStatus::Downloading(rx));
+        game.clone()
+    };
+    bramlett::download::install_game(&ctx, game, tx)
+        .await
+        .unwrap();
+    assert!(config.game_dir(GameId(0)).join("game.exe").is_file());
+}
+
+/// A host the app doesn't know about, serving the archive from the mock server.
+struct OtherHost(SocketAddr);
+
+impl fmt::Display for OtherHost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "other host at {}", self.0)
+    }
+}
+
+impl DownloadSource for OtherHost {
+    async fn fetch(
+        &self,
+        ctx: &Ctx,
+        _resume: Option<&PartialDownload>,
+    ) -> bramlett::Result<reqwest::Response> {
+        Ok(ctx
+            .client
+            .get(format!("http://{}/uc", self.0))
+            .send()
+            .await?)
+    }
+}
+
+#[tokio::test]
+async fn new_hosts_only_need_a_download_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let addr = serve(vec![game_info(0, "Zero")]);
+    let config = config(addr, &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let game = config.games().get(&GameId(0)).unwrap().clone();
+    let (tx, _rx) = watch::channel((0, 0));
+    let path = bramlett::download::download_game(game, ctx, &OtherHost(addr), &tx)
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read(path).unwrap(), ARCHIVE);
+}
+
+#[tokio::test]
+async fn imported_archive_is_installed() {
+    let dir = tempfile::tempdir().unwrap();
+    // nothing should be downloaded
+    let config = config(SocketAddr::from(([127, 0, 0, 1], 9)), &dir);
+    config.games().insert(
+        GameId(0),
+        Game {
+            info: game_info(0, "Zero"),
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+    let usb = dir.path().join("usb");
+    std::fs::create_dir_all(&usb).unwrap();
+    std::fs::write(usb.join("zero.7z"), ARCHIVE).unwrap();
+    std::fs::write(usb.join("notes.7z"), "not an archive").unwrap();
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let import = |path: PathBuf| {
+        let (tx, rx) = watch::channel((0, 0));
+        let game = {
+            let mut game = games.get_mut(&GameId(0)).unwrap();
+            config.set_status(&mut game, GameStatus::Downloading(rx));
+            game.clone()
+        };
+        bramlett::download::import_archive(&ctx, game, path, tx)
+    };
+
+    assert!(import(usb.join("notes.7z")).await.is_err());
+    assert!(matches!(
+        games.get(&GameId(0)).unwrap().status,
+        GameStatus::Failed(_)
+    ));
+    assert!(!config.archive_path(GameId(0)).exists());
+
+    import(usb.join("zero.7z")).await.unwrap();
+    assert!(config.game_dir(GameId(0)).join("game.exe").is_file());
+    assert!(usb.join("zero.7z").is_file());
+}
+
+#[tokio::test]
+async fn failed_extraction_keeps_the_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let install = || {
+        let (tx, rx) = watch::channel((0, 0));
+        let game = {
+            let mut game = games.get_mut(&GameId(0)).unwrap();
+            config.set_status(&mut game, GameStatus::Downloading(rx));
+            game.clone()
+        };
+        bramlett::download::install_game(&ctx, game, tx)
+    };
+
+    // a file where the game's directory should go makes extraction fail
+    let game_dir = config.game_dir(GameId(0));
+    std::fs::create_dir_all(config.games_dir()).unwrap();
+    std::fs::write(&game_dir, "in the way").unwrap();
+    assert!(install().await.is_err());
+    assert!(config.archive_path(GameId(0)).is_file());
+    let stages: Vec<_> = config
+        .error_history(GameId(0))
+        .iter()
+        .map(|e| e.stage)
+        .collect();
+    assert_eq!(stages, [ErrorStage::Install]);
+
+    // the retry can't download anything, so it has to use the kept archive
+    std::fs::remove_file(&game_dir).unwrap();
+    config.set_drive_url(Some("http://127.0.0.1:9".into()));
+    install().await.unwrap();
+    assert!(game_dir.join("game.exe").is_file());
+    assert!(!config.archive_path(GameId(0)).exists());
+}
+
+#[tokio::test]
+async fn archive_of_an_old_version_isnt_reused() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    let games = config.games();
+    let install = || {
+        let (tx, rx) = watch::channel((0, 0));
+        let game = {
+            let mut game = games.get_mut(&GameId(0)).unwrap();
+            config.set_status(&mut game, GameStatus::Downloading(rx));
+            game.clone()
+        };
+        bramlett::download::install_game(&ctx, game, tx)
+    };
+
+    let game_dir = config.game_dir(GameId(0));
+    std::fs::create_dir_all(config.games_dir()).unwrap();
+    std::fs::write(&game_dir, "in the way").unwrap();
+    assert!(install().await.is_err());
+    assert!(config.archive_path(GameId(0)).is_file());
+
+    // a new version came out, which can't be downloaded
+    std::fs::remove_file(&game_dir).unwrap();
+    games.get_mut(&GameId(0)).unwrap().info.changelog = Some("v2".into());
+    config.set_drive_url(Some("http://127.0.0.1:9".into()));
+    assert!(install().await.is_err());
+    assert!(!game_dir.exists());
+    assert!(!config.archive_path(GameId(0)).exists());
+}
+
+#[tokio::test]
+async fn error_history_keeps_the_latest_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![]), &dir);
+    for i in 0..ERROR_HISTORY_LEN + 3 {
+        config.record_error(GameId(0), ErrorStage::Run, format!("error {i}"));
+    }
+    let errors = config.error_history(GameId(0));
+    assert_eq!(errors.len(), ERROR_HISTORY_LEN);
+    assert_eq!(errors[0].message, "error 3");
+    assert_eq!(
+        errors.last().unwrap().message,
+        format!("error {}", ERROR_HISTORY_LEN + 2)
+    );
+    assert!(config.error_history(GameId(1)).is_empty());
+
+    // the history is saved with the config
+    let saved: Config = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+    assert_eq!(saved.error_history(GameId(0)), errors);
+
+    config.clear_error_history(GameId(0));
+    assert!(config.error_history(GameId(0)).is_empty());
+}
+
+/// Waits until the install queue isn't `len` long anymore.
+async fn queue_changed(config: &Config, len: usize) -> Vec<GameId> {
+    loop {
+        let queue = config.install_queue();
+        if queue.len() != len {
+            return queue;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn install_queue_can_be_reordered() {
+    let dir = tempfile::tempdir().unwrap();
+    let games = (0..3).map(|id| game_info(id, "Queued")).collect();
+    let config = config(serve(games), &dir);
+    update_game_list(&config, true).await.unwrap();
+
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    ctx.set_max_concurrent_work(1).unwrap();
+    let busy = ctx.start_work(Work::Download).await;
+
+    let games = config.games();
+    let mut installs = Vec::new();
+    let mut progress = Vec::new();
+    for id in 0..3 {
+        let (tx, rx) = watch::channel((0, 0));
+        progress.push(rx.clone());
+        let game = {
+            let mut game = games.get_mut(&GameId(id)).unwrap();
+            config.set_status(&mut game, GameStatus::Downloading(rx));
+            game.clone()
+        };
+        let ctx = ctx.clone();
+        installs.push(tokio::spawn(async move {
+            bramlett::download::install_game(&ctx, game, tx).await
+        }));
+        queue_changed(&config, usize::try_from(id).unwrap()).await;
+    }
+    assert_eq!(config.install_queue(), [GameId(0), GameId(1), GameId(2)]);
+
+    // the queue is saved with the config, so it's picked up again after a restart
+    let saved: Config = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+    assert_eq!(saved.install_queue(), config.install_queue());
+
+    assert!(ctx.move_in_install_queue(GameId(2), 0));
+    assert!(ctx.remove_from_install_queue(GameId(1)));
+    assert!(!ctx.remove_from_install_queue(GameId(1)));
+    assert_eq!(config.install_queue(), [GameId(2), GameId(0)]);
+
+    // sends each game's ID when its download starts
+    let (started_tx, mut started) = mpsc::unbounded_channel();
+    for (id, mut progress) in progress.into_iter().enumerate() {
+        let started_tx = started_tx.clone();
+        tokio::spawn(async move {
+            if progress.changed().await.is_ok() {
+                started_tx.send(id).unwrap();
+            }
+        });
+    }
+    drop(started_tx);
+
+    drop(busy);
+    // the game moved to the front goes first
+    assert_eq!(started.recv().await, Some(2));
+    assert_eq!(started.recv().await, Some(0));
+    assert_eq!(started.recv().await, None);
+
+    let mut installs = installs.into_iter();
+    assert!(installs.next().unwrap().await.unwrap().is_ok());
+    assert!(matches!(
+        installs.next().unwrap().await.unwrap(),
+        Err(ClientError::Canceled)
+    ));
+    assert!(installs.next().unwrap().await.unwrap().is_ok());
+    assert!(config.install_queue().is_empty());
+}
+
+#[tokio::test]
+async fn queued_downloads_wait_for_the_download_window() {
+    // it's written like this in the config file, and wraps around midnight
+    let night: DownloadWindow =
+        serde_json::from_str(r#"{"start": "22:00", "end": "06:30"}"#).unwrap();
+    let at = |hour: u32, minute: u32| (hour * 60 + minute) * 60;
+    assert!(night.is_open(at(23, 0)) && night.is_open(at(6, 29)));
+    assert!(!night.is_open(at(6, 30)) && !night.is_open(at(12, 0)));
+    assert_eq!(
+        night.next_change(at(12, 0)),
+        Some(Duration::from_secs(10 * 60 * 60))
+    );
+    assert_eq!(
+        night.next_change(at(23, 0)),
+        Some(Duration::from_secs(7 * 60 * 60 + 30 * 60))
+    );
+    assert!("24:00".parse::<TimeOfDay>().is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(serve(vec![game_info(0, "Zero")]), &dir);
+    update_game_list(&config, true).await.unwrap();
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+
+    // opens in an hour, for an hour
+    let minutes = |offset: u32| {
+        let minutes = (schedule::now() / 60 + offset) % (24 * 60);
+        TimeOfDay::new((minutes / 60) as u16, (minutes % 60) as u16).unwrap()
+    };
+    ctx.set_download_window(Some(DownloadWindow {
+        start: minutes(60),
+        end: minutes(120),
+    }));
+    assert!(!config.download_window_open());
+
+    let (tx, rx) = watch::channel((0, 0));
+    let game = {
+        let games = config.games();
+        let mut game = games.get_mut(&GameId(0)).unwrap();
+        config.set_status(&mut game, GameStatus::Downloading(rx.clone()));
+        game.clone()
+    };
+    let install = tokio::spawn({
+        let ctx = ctx.clone();
+        async move { bramlett::download::install_game(&ctx, game, tx).await }
+    });
+    queue_changed(&config, 0).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!install.is_finished());
+    assert_eq!(*rx.borrow(), (0, 0));
+    assert_eq!(config.install_queue(), [GameId(0)]);
+
+    ctx.set_download_window(None);
+    tokio::time::timeout(Duration::from_secs(5), install)
+        .await
+        .expect("the download didn't start once the window was open")
+        .unwrap()
+        .unwrap();
+    assert!(config.game_dir(GameId(0)).join("game.exe").is_file());
+}
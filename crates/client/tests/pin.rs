@@ -0,0 +1,77 @@
+//! Checks reading pinned keys and that the games server has to use one.
+
+use bramlett::pin::{parse_pins, Pin};
+use bramlett::{update_game_list, ClientError, Config};
+use common::GameInfo;
+use warp::Filter;
+
+/// A self-signed certificate for `games.example`.
+const CERTIFICATE: &[u8] = include_bytes!("fixtures/server.der");
+/// [`CERTIFICATE`]'s pin, from
+/// `openssl x509 -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64`.
+const PIN: &str = "sha256//VFudV5ofgOlUjkFEMzmXD1FHewPiWbBJIAa6s+HFnsU=";
+
+#[test]
+fn pin_is_the_hash_of_the_public_key() {
+    let pin = Pin::of(CERTIFICATE).unwrap();
+    assert_eq!(pin, PIN.parse().unwrap());
+    assert_eq!(pin, PIN["sha256//".len()..].parse().unwrap());
+    assert_eq!(Pin::of(&CERTIFICATE[..100]), None);
+    assert_eq!(Pin::of(b""), None);
+}
+
+#[test]
+fn invalid_pins_are_rejected() {
+    let other = format!("sha256//{}", "A".repeat(43) + "=");
+    assert_eq!(parse_pins(&format!("{PIN}; {other};")).unwrap().len(), 2);
+    for pin in [
+        "",
+        " ; ",
+        "sha256//abc",
+        "sha1//VFudV5ofgOlUjkFEMzmXD1FHewPiWbBJIAa6s+HFnsU=",
+    ] {
+        assert!(
+            matches!(parse_pins(pin), Err(ClientError::InvalidPin(_))),
+            "{pin:?} was accepted"
+        );
+    }
+
+    let config = Config::default();
+    assert!(config.set_server_pin(Some("not a pin".into())).is_err());
+    assert_eq!(config.server_pin(), None);
+    config.set_server_pin(Some(PIN.into())).unwrap();
+    assert_eq!(config.server_pin().as_deref(), Some(PIN));
+}
+
+#[tokio::test]
+async fn unpinned_server_is_rejected() {
+    // keep the test from touching the real config file
+    std::env::set_var(
+        "BRAMLETT_CONFIG_DIR",
+        std::env::temp_dir().join("bramlett-tests"),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let games = warp::path("games").map(|| {
+        warp::reply::json(&[GameInfo {
+            name: "Zero".into(),
+            exe: "game.exe".into(),
+            ..Default::default()
+        }])
+    });
+    let (addr, server) = warp::serve(games).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    let config = Config::default();
+    config.set_games_dir(dir.path().join("games"));
+    config.set_server_url(Some(format!("http://{addr}")));
+    config.set_server_pin(Some(PIN.into())).unwrap();
+    // a server that isn't using TLS can't have the pinned key either
+    assert!(matches!(
+        update_game_list(&config, true).await,
+        Err(ClientError::PinMismatch(host)) if host == "127.0.0.1"
+    ));
+    assert!(config.games().is_empty());
+
+    config.set_server_pin(None).unwrap();
+    assert_eq!(update_game_list(&config, true).await.unwrap(), 1);
+}
@@ -0,0 +1,38 @@
+//! Checks that the proxy from the environment is used when none is configured. It's kept apart
+//! from the other tests, since the environment variables apply to the whole process.
+
+use bramlett::{update_game_list, Config};
+use common::{GameId, GameInfo};
+use warp::Filter;
+
+#[tokio::test]
+async fn the_environments_proxy_is_used_unless_the_host_is_excluded() {
+    // keep the test from touching the real config file
+    std::env::set_var(
+        "BRAMLETT_CONFIG_DIR",
+        std::env::temp_dir().join("bramlett-tests"),
+    );
+    // stands in for a proxy, answering with a game list whichever server it's asked for
+    let games = warp::path("games").map(|| {
+        warp::reply::json(&vec![GameInfo {
+            name: "Zero".into(),
+            id: GameId(0),
+            exe: "game.exe".into(),
+            ..Default::default()
+        }])
+    });
+    let (proxy, server) = warp::serve(games).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    let config = Config::default();
+    // it doesn't exist, so the list can only come through the proxy
+    config.set_server_url(Some("http://games.invalid".into()));
+    for name in ["NO_PROXY", "no_proxy"] {
+        std::env::remove_var(name);
+    }
+    std::env::set_var("HTTPS_PROXY", format!("http://{proxy}"));
+    assert_eq!(update_game_list(&config, true).await.unwrap(), 1);
+
+    std::env::set_var("NO_PROXY", "localhost, .invalid");
+    assert!(update_game_list(&config, true).await.is_err());
+}
@@ -0,0 +1,255 @@
+//! Checks that hooks run on the Python thread, and that progress they report and hooks that
+//! fail show up on the game.
+
+use bramlett::py::{
+    handle_responses, py_loop, run_hook, run_with_hooks, HookStatus, RequestTy, Response,
+};
+use bramlett::{ClientError, Config, Ctx, Game, GameStatus};
+use common::{GameId, GameInfo};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+async fn hook_progress_sets_installing_status() {
+    let config = Config::default();
+    let id = GameId(7);
+    config.games().insert(
+        id,
+        Game {
+            info: GameInfo {
+                id,
+                ..Default::default()
+            },
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+    let (py_tx, _py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let handler = tokio::spawn(handle_responses(ctx, rx));
+    tx.send(Response::Progress {
+        id,
+        done: 1,
+        total: 4,
+    })
+    .unwrap();
+    tx.send(Response::Progress {
+        id,
+        done: 3,
+        total: 4,
+    })
+    .unwrap();
+    let games = config.games();
+    loop {
+        if let GameStatus::Installing(progress) = &games.get(&id).unwrap().status {
+            if *progress.borrow() == (3, 4) {
+                break;
+            }
+        }
+        tokio::task::yield_now().await;
+    }
+
+    // the game goes back to how it was once the hook returns
+    tx.send(Response::Finished { id }).unwrap();
+    drop(tx);
+    handler.await.unwrap();
+    assert!(matches!(
+        games.get(&id).unwrap().status,
+        GameStatus::NotDownloaded
+    ));
+}
+
+#[tokio::test]
+async fn failed_hooks_are_kept_per_game() {
+    let (py_tx, mut py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(Config::default(), reqwest::Client::new(), py_tx);
+    let id = GameId(7);
+
+    let python = tokio::spawn({
+        let ctx = ctx.clone();
+        async move {
+            let request: bramlett::py::Request = py_rx.recv().await.unwrap();
+            assert_eq!(
+                ctx.hooks.get(&id).as_deref(),
+                Some(&HookStatus::Running(RequestTy::PostInstall))
+            );
+            let _ = request.finish.send(Ok(()));
+        }
+    });
+    run_hook(&ctx, id, RequestTy::PostInstall, false)
+        .await
+        .unwrap();
+    python.await.unwrap();
+    assert!(ctx.hooks.get(&id).is_none());
+
+    // the python thread has stopped
+    assert!(matches!(
+        run_hook(&ctx, id, RequestTy::PreRun, false).await,
+        Err(ClientError::PythonUnavailable)
+    ));
+    let Some(HookStatus::Failed { hook, .. }) = ctx.hooks.get(&id).as_deref().cloned() else {
+        panic!("the failure wasn't kept");
+    };
+    assert_eq!(hook, RequestTy::PreRun);
+}
+
+#[tokio::test]
+async fn hooks_run_on_the_python_thread() {
+    let config = Config::default();
+    let id = GameId(7);
+    config.games().insert(
+        id,
+        Game {
+            info: GameInfo {
+                id,
+                hooks: "def post_install():\n    report_progress(1, 2)\n\n\
+                        def pre_run():\n    raise ValueError('broken')\n"
+                    .into(),
+                ..Default::default()
+            },
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+    let (py_tx, py_rx) = mpsc::unbounded_channel();
+    let (responses_tx, mut responses_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config, reqwest::Client::new(), py_tx);
+    std::thread::spawn({
+        let ctx = ctx.clone();
+        move || py_loop(py_rx, responses_tx, ctx)
+    });
+
+    run_hook(&ctx, id, RequestTy::PostInstall, false)
+        .await
+        .unwrap();
+    assert_eq!(
+        responses_rx.recv().await,
+        Some(Response::Progress {
+            id,
+            done: 1,
+            total: 2
+        })
+    );
+    assert_eq!(responses_rx.recv().await, Some(Response::Finished { id }));
+
+    // games don't have to have every hook
+    run_hook(&ctx, id, RequestTy::PostRun, false).await.unwrap();
+    let res = run_hook(&ctx, id, RequestTy::PreRun, false).await;
+    assert!(
+        matches!(&res, Err(ClientError::HookFailed(e)) if e.contains("broken")),
+        "{res:?}"
+    );
+}
+
+#[tokio::test]
+async fn hooks_know_where_the_game_and_its_saves_are() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::default();
+    config.set_games_dir(dir.path().join("games"));
+    config.set_saves_dir(dir.path().join("saves"));
+    let id = GameId(7);
+    config.games().insert(
+        id,
+        Game {
+            info: GameInfo {
+                id,
+                hooks: "def post_install():\n    \
+                        with open(game_dir + '/dirs.txt', 'w') as f:\n        \
+                        f.write(game_dir + '\\n' + save_dir)\n"
+                    .into(),
+                ..Default::default()
+            },
+            status: GameStatus::NotDownloaded,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+    let game_dir = config.game_dir(id);
+    std::fs::create_dir_all(&game_dir).unwrap();
+    let (py_tx, py_rx) = mpsc::unbounded_channel();
+    let (responses_tx, _responses_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config.clone(), reqwest::Client::new(), py_tx);
+    std::thread::spawn({
+        let ctx = ctx.clone();
+        move || py_loop(py_rx, responses_tx, ctx)
+    });
+
+    run_hook(&ctx, id, RequestTy::PostInstall, false)
+        .await
+        .unwrap();
+    let dirs = std::fs::read_to_string(game_dir.join("dirs.txt")).unwrap();
+    assert_eq!(
+        dirs,
+        format!("{}\n{}", game_dir.display(), config.save_dir(id).display())
+    );
+}
+
+#[tokio::test]
+async fn games_run_between_their_pre_run_and_post_run_hooks() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config::default();
+    config.set_games_dir(dir.path().to_path_buf());
+    let id = GameId(7);
+    config.games().insert(
+        id,
+        Game {
+            info: GameInfo {
+                id,
+                hooks: "def pre_run():\n    open(game_dir + '/pre_run', 'w').close()\n\n\
+                        def post_run():\n    open(game_dir + '/post_run', 'w').close()\n"
+                    .into(),
+                ..Default::default()
+            },
+            status: GameStatus::Ready,
+            partial: None,
+            hooks_error: None,
+        },
+    );
+    let game_dir = config.game_dir(id);
+    std::fs::create_dir_all(&game_dir).unwrap();
+    let (py_tx, py_rx) = mpsc::unbounded_channel();
+    let (responses_tx, _responses_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(config, reqwest::Client::new(), py_tx);
+    std::thread::spawn({
+        let ctx = ctx.clone();
+        move || py_loop(py_rx, responses_tx, ctx)
+    });
+
+    let ran = run_with_hooks(&ctx, id, async {
+        assert!(game_dir.join("pre_run").exists());
+        assert!(!game_dir.join("post_run").exists());
+        true
+    })
+    .await;
+    assert!(ran);
+    assert!(game_dir.join("post_run").exists());
+    assert!(ctx.hooks.get(&id).is_none());
+}
+
+#[tokio::test]
+async fn a_failed_pre_run_hook_doesnt_stop_the_game() {
+    let (py_tx, mut py_rx) = mpsc::unbounded_channel();
+    let ctx = Ctx::new(Config::default(), reqwest::Client::new(), py_tx);
+    let id = GameId(7);
+    // answers pre_run with an error and post_run with success
+    let python = tokio::spawn(async move {
+        let pre_run = py_rx.recv().await.unwrap();
+        assert_eq!(pre_run.ty, RequestTy::PreRun);
+        let _ = pre_run
+            .finish
+            .send(Err(bramlett::py::Error::Python("broken".into())));
+        let post_run = py_rx.recv().await.unwrap();
+        assert_eq!(post_run.ty, RequestTy::PostRun);
+        let _ = post_run.finish.send(Ok(()));
+    });
+
+    assert!(run_with_hooks(&ctx, id, async { true }).await);
+    python.await.unwrap();
+    let Some(HookStatus::Failed { hook, .. }) = ctx.hooks.get(&id).as_deref().cloned() else {
+        panic!("the failure wasn't kept");
+    };
+    assert_eq!(hook, RequestTy::PreRun);
+}
@@ -0,0 +1,63 @@
+//! Checks that a game's usage counts the processes it started, and that they keep it running.
+
+use bramlett::usage::UsageTracker;
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+#[cfg(unix)]
+#[test]
+fn counts_child_processes() {
+    let started = SystemTime::now();
+    // the shell waits for sleep instead of becoming it, so there are two processes
+    let mut launcher = Command::new("sh")
+        .args(["-c", "sleep 5; true"])
+        .spawn()
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+
+    let mut tracker = UsageTracker::new(launcher.id(), started);
+    let usage = tracker.measure();
+    launcher.kill().unwrap();
+    launcher.wait().unwrap();
+
+    assert_eq!(usage.processes, 2);
+    assert!(usage.memory > 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn tracks_processes_after_the_launcher_exits() {
+    let started = SystemTime::now();
+    let mut launcher = Command::new("sh")
+        .args(["-c", "sleep 1; true"])
+        .spawn()
+        .unwrap();
+    std::thread::sleep(Duration::from_millis(300));
+
+    let mut tracker = UsageTracker::new(launcher.id(), started);
+    assert_eq!(tracker.measure().processes, 2);
+    launcher.kill().unwrap();
+    launcher.wait().unwrap();
+    assert_eq!(tracker.measure().processes, 1);
+
+    std::thread::sleep(Duration::from_millis(1500));
+    assert_eq!(tracker.measure().processes, 0);
+}
+
+#[cfg(windows)]
+#[test]
+fn tracks_processes_when_the_launcher_exits_before_tracking_starts() {
+    let started = SystemTime::now();
+    // `start /b` runs ping without waiting for it, so cmd exits right away
+    let mut launcher = Command::new("cmd")
+        .args(["/c", "start", "/b", "ping", "-n", "3", "127.0.0.1"])
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    launcher.wait().unwrap();
+
+    let mut tracker = UsageTracker::new(launcher.id(), started);
+    assert_eq!(tracker.measure().processes, 1);
+    std::thread::sleep(Duration::from_secs(4));
+    assert_eq!(tracker.measure().processes, 0);
+}
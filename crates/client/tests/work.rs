@@ -0,0 +1,60 @@
+//! Checks the limit on concurrent downloads and extractions.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bramlett::{Work, WorkLimiter};
+use tokio::sync::mpsc;
+
+#[tokio::test]
+async fn extractions_go_before_new_downloads() {
+    let limiter = Arc::new(WorkLimiter::default());
+    let running = limiter.acquire(Work::Download, || 1).await;
+    assert_eq!(limiter.running(), 1);
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    for work in [Work::Download, Work::Extraction] {
+        let limiter = limiter.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let _permit = limiter.acquire(work, || 1).await;
+            tx.send(work).unwrap();
+        });
+        // lets it start waiting before the next one does
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(rx.try_recv().is_err());
+
+    drop(running);
+    assert_eq!(rx.recv().await, Some(Work::Extraction));
+    assert_eq!(rx.recv().await, Some(Work::Download));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(limiter.running(), 0);
+}
+
+#[tokio::test]
+async fn raising_the_limit_starts_waiting_work() {
+    let limiter = Arc::new(WorkLimiter::default());
+    let limit = Arc::new(AtomicUsize::new(1));
+    let _running = limiter.acquire(Work::Download, || 1).await;
+
+    let waiting = tokio::spawn({
+        let limiter = limiter.clone();
+        let limit = limit.clone();
+        async move {
+            let _permit = limiter
+                .acquire(Work::Download, || limit.load(Ordering::Relaxed))
+                .await;
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiting.is_finished());
+
+    limit.store(2, Ordering::Relaxed);
+    limiter.limit_changed();
+    tokio::time::timeout(Duration::from_secs(1), waiting)
+        .await
+        .expect("the waiting download didn't start")
+        .unwrap();
+}
@@ -5,6 +5,7 @@ use juniper::{GraphQLObject, GraphQLScalar};
 // / The ID of a game.
 #[derive(
     Debug,
+    Default,
     Copy,
     Clone,
     Hash,
@@ -28,6 +29,7 @@ impl Display for GameId {
 
 #[derive(
     Debug,
+    Default,
     Clone,
     Hash,
     PartialEq,
@@ -45,8 +47,13 @@ pub struct GameInfo {
     pub id: GameId,
     /// A link to the game's icon.
     pub icon: String,
-    /// A public Google Drive ID, linking to a zip file with a password of "game".
+    /// A public Google Drive ID, linking to a 7zip file with a password of [`Self::zip_password`].
     pub gdrive_id: String,
+    /// Other places the same archive can be downloaded from, tried in order if downloading it
+    /// from Google Drive fails.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub mirrors: Vec<GameSource>,
     /// The name of the game's executable, relative to the game directory.
     pub exe: String,
     /// python script with `post_install`, `pre_run` and `post_run` functions.
@@ -58,8 +65,120 @@ pub struct GameInfo {
     ///
     /// # Constants
     ///
-    /// `game_dir`: `PathBuf` to the game directory.
+    /// `game_dir`: the path of the game directory, as a `str`.
     ///
-    /// `save_dir`: `PathBuf` to the save directory.
+    /// `save_dir`: the path of the save directory, as a `str`.
     pub hooks: String,
+    /// A short description of the game.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// What changed in the latest version of the game's files.
+    #[serde(default)]
+    pub changelog: Option<String>,
+    /// A web page about the game, like one explaining its controls. Only http and https links are
+    /// opened.
+    #[serde(default)]
+    pub homepage: Option<String>,
+    /// Lowercase categories the game can be filtered by, like "puzzle" or "multiplayer".
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Files to delete after each time the game is run, like logs or crash dumps that pile up.
+    ///
+    /// Patterns are matched against paths relative to the game directory, with `/` separators.
+    /// `*` and `?` match within a path component and `**` matches any number of directories, like
+    /// `logs/**/*.dmp`. Patterns starting with `$TEMP/` are matched in the system's temp
+    /// directory instead, and have to name the game's own file or folder there before any
+    /// wildcards, like `$TEMP/MyGame/*.log`.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub cleanup_globs: Vec<String>,
+    /// How many leading directories to remove from the paths of the files in the archive, for
+    /// archives that nest the game deeper than usual or not at all. `None` removes the archive's
+    /// top-level directory, if every file is in one.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub strip_prefix: Option<u32>,
+    /// The archive's password, if it isn't one of the usual ones (like "game").
+    #[serde(default)]
+    #[graphql(skip)]
+    pub zip_password: Option<String>,
+    /// Whether the game has to run as administrator. On Windows, running it asks for permission
+    /// with a UAC prompt. Elsewhere it's run normally, with a note in its output.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub requires_admin: bool,
+    /// Windows compatibility settings for the game's executable, applied before it's run.
+    #[serde(default)]
+    #[graphql(skip)]
+    pub compat: Compat,
+}
+
+impl GameInfo {
+    /// Everywhere the game's archive can be downloaded from, in the order they should be tried.
+    pub fn sources(&self) -> Vec<GameSource> {
+        std::iter::once(GameSource::GoogleDrive(self.gdrive_id.clone()))
+            .filter(|s| !matches!(s, GameSource::GoogleDrive(id) if id.is_empty()))
+            .chain(self.mirrors.iter().cloned())
+            .collect()
+    }
+}
+
+/// Windows compatibility settings for a game's executable, like the ones on its Compatibility tab
+/// in Explorer. Older games can need them to show anything but a black screen.
+///
+/// Running as administrator is [`GameInfo::requires_admin`] instead, since it needs a UAC prompt.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(default)]
+pub struct Compat {
+    /// Turns off fullscreen optimizations, which some older DirectX games break under.
+    pub disable_fullscreen_optimizations: bool,
+    /// What scales the game on high DPI displays, instead of the game deciding.
+    pub dpi_scaling: Option<DpiScaling>,
+}
+
+/// What scales a game on high DPI displays, see [`Compat::dpi_scaling`].
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DpiScaling {
+    /// The game, even if it doesn't say it can.
+    Application,
+    /// Windows, which stretches the game so it's blurry.
+    System,
+    /// Windows, keeping text and shapes the game draws with GDI sharp.
+    SystemEnhanced,
+}
+
+/// Somewhere a game's archive can be downloaded from.
+#[derive(
+    Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum GameSource {
+    /// A public Google Drive file ID.
+    GoogleDrive(String),
+    /// A URL the archive can be downloaded from directly.
+    Direct(String),
+}
+
+impl Display for GameSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GoogleDrive(id) => write!(f, "Google Drive ({id})"),
+            Self::Direct(url) => url.fmt(f),
+        }
+    }
 }
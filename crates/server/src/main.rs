@@ -1,4 +1,7 @@
 use common::{GameId, GameInfo};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use warp::http::StatusCode;
 use warp::Filter;
 use warp::Reply;
 
@@ -9,6 +12,10 @@ macro_rules! script {
     };
 }
 
+fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|&t| t.to_owned()).collect()
+}
+
 fn games() -> Vec<GameInfo> {
     vec![
         GameInfo {
@@ -18,6 +25,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1sBkd4vADCCH3WmoF6DqtKhjEt7G3D307".into(),
             exe: "PapersPlease.exe".into(),
             hooks: script!("papers_please"),
+            tags: tags(&["puzzle", "story"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Geometry Dash".into(),
@@ -26,6 +35,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "16CYi7pAMTsmbVmlEtUBOkLjvpqtQdght".into(),
             exe: "steamapps/common/Geometry Dash/GeometryDash.exe".into(),
             hooks: script!("geometry_dash"),
+            tags: tags(&["platformer", "rhythm"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Bloons TD 6".into(),
@@ -34,6 +45,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1yd62Hz-e4d_Z0gilXc18dRRmoD5o0WSY".into(),
             exe: "BloonsTD6.exe".into(),
             hooks: script!("bloons_td_6"),
+            tags: tags(&["strategy", "tower defense"]),
+            ..Default::default()
         },
         GameInfo {
             name: "OMORI".into(),
@@ -42,6 +55,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "13QjrN_I8ccliWslVMINW2HNSNVxsqkSp".into(),
             exe: "OMORI.exe".into(),
             hooks: script!("omori"),
+            tags: tags(&["rpg", "story", "horror"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Totally Accurate Battle Simulator".into(),
@@ -50,6 +65,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1KiuU7rf9BK6v3_TJTbRw_HLi9hUSWByJ".into(),
             exe: "TotallyAccurateBattleSimulator.exe".into(),
             hooks: script!("tabs"),
+            tags: tags(&["strategy", "sandbox"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Half-Life".into(),
@@ -58,6 +75,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1TTHHjQWSu_KBwTv6ox7pppsFGd-8t95V".into(),
             exe: "hl.exe".into(),
             hooks: script!("half_life"),
+            tags: tags(&["shooter", "story"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Call of Duty 2".into(),
@@ -66,6 +85,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1GtNsZggdQkyLK8Seiem7KGhRIyFHFP7C".into(),
             exe: "CoD2SP_s.exe".into(),
             hooks: script!("cod2"),
+            tags: tags(&["shooter", "war"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Portal".into(),
@@ -74,6 +95,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1B_GDs711J30mCwMU8F12L8fBKX4dbYJM".into(),
             exe: "Portal.exe".into(),
             hooks: script!("portal"),
+            tags: tags(&["puzzle", "story"]),
+            ..Default::default()
         },
         GameInfo {
             name: "SUPERHOT".into(),
@@ -82,6 +105,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1qZSFbucjmEmhkbr9y93csZklYtkfzibK".into(),
             exe: "SUPERHOT.exe".into(),
             hooks: script!("superhot"),
+            tags: tags(&["shooter", "puzzle"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Five Nights at Freddy's".into(),
@@ -90,6 +115,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1TddScp06i7Echbh-JAa_PKTce3GyBRhg".into(),
             exe: "FiveNightsatFreddys.exe".into(),
             hooks: script!("fnaf"),
+            tags: tags(&["horror"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Five Nights at Freddy's 2".into(),
@@ -98,6 +125,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1C-3vrgV0gaLeZ5b19WKRroQUJRkhs5AD".into(),
             exe: "FiveNightsatFreddys2.exe".into(),
             hooks: script!("fnaf2"),
+            tags: tags(&["horror"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Five Nights at Freddy's 3".into(),
@@ -106,6 +135,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1Qu2_VRYU_Fm1_gDLw264oVzvfIjw8f42".into(),
             exe: "FiveNightsatFreddys3.exe".into(),
             hooks: script!("fnaf3"),
+            tags: tags(&["horror"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Five Nights at Freddy's 4".into(),
@@ -114,6 +145,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1Q2KZvvSimGWjDWmRnvvqcGZMtqjw2EM3".into(),
             exe: "FiveNightsatFreddys4.exe".into(),
             hooks: script!("fnaf4"),
+            tags: tags(&["horror"]),
+            ..Default::default()
         },
         GameInfo {
             name: "UNDERTALE".into(),
@@ -122,6 +155,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1DEnp81K_zmy-l5CRsyXCl6gVKCNakwiN".into(),
             exe: "Undertale.exe".into(),
             hooks: script!("undertale"),
+            tags: tags(&["rpg", "story"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Call of Duty".into(),
@@ -130,6 +165,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1Dxxdcj7sDNgNcPfcqI_-5ZBYK-lNCywI".into(), 
             exe: "CoDSP.exe".into(),
             hooks: script!("cod"),
+            tags: tags(&["shooter", "war"]),
+            ..Default::default()
         },
         // GameInfo {
         //     name: "Call of Duty 4: Modern Warfare".into(),
@@ -154,6 +191,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "14k2cdhdigdgB3mDSSveOJmTBLVCtwfpD".into(),
             exe: "gta_sa.exe".into(),
             hooks: script!("gta_sa"),
+            tags: tags(&["open world", "action"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Grand Theft Auto: Vice City".into(),
@@ -162,6 +201,8 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "1M4DlCIqeOvevPZ7aLUNLDiiGAqyxa5ct".into(),
             exe: "gta-vc.exe".into(),
             hooks: script!("gta_vc"),
+            tags: tags(&["open world", "action"]),
+            ..Default::default()
         },
         GameInfo {
             name: "Getting Over It".into(),
@@ -170,22 +211,52 @@ fn games() -> Vec<GameInfo> {
             gdrive_id: "13feVmY99R_xQIQtFPRnyUIFgIjcHoWKP".into(),
             exe: "GettingOverIt.exe".into(), // fix this
             hooks: script!("getting_over_it"),
+            tags: tags(&["platformer"]),
+            ..Default::default()
         }
     ]
 }
 
+/// Replies with the game list, tagged with an `ETag` so clients can skip downloading it again if
+/// it hasn't changed.
+fn games_reply(if_none_match: Option<String>) -> warp::reply::Response {
+    let body = serde_json::to_vec(&games()).unwrap();
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+    if if_none_match.is_some_and(|tags| etag_matches(&tags, &etag)) {
+        return StatusCode::NOT_MODIFIED.into_response();
+    }
+    warp::http::Response::builder()
+        .header("content-type", "application/json")
+        .header("etag", etag)
+        .body(body.into())
+        .unwrap()
+}
+
+/// Whether an `If-None-Match` header matches `etag`. The header can list several tags (or `*`),
+/// and they're compared weakly, so a `W/` prefix is ignored.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
 #[shuttle_runtime::main]
 async fn warp() -> shuttle_warp::ShuttleWarp<(impl Reply,)> {
     let route = warp::get()
         .and(
             warp::path("games")
                 .and(warp::path::end())
-                .map(|| warp::reply::json(&games())),
+                .and(warp::header::optional("if-none-match"))
+                .map(games_reply),
         )
         .with(
             warp::cors()
                 .allow_any_origin()
-                .allow_headers(["Content-Type", "User-Agent"])
+                .allow_headers(["Content-Type", "User-Agent", "If-None-Match"])
                 .allow_methods(["OPTIONS", "GET", "POST", "DELETE"]),
         );
     Ok(route.boxed().into())
@@ -198,3 +269,43 @@ fn assert_unique_ids() {
         assert!(seen_ids.insert(game.id));
     }
 }
+
+#[cfg(test)]
+#[test]
+fn assert_unique_names() {
+    let mut seen_names = std::collections::HashSet::new();
+    for game in games() {
+        assert!(seen_names.insert(game.name.clone()), "{}", game.name);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn assert_lowercase_tags() {
+    for game in games() {
+        for tag in &game.tags {
+            assert_eq!(tag, &tag.to_lowercase(), "{}", game.name);
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn unchanged_games_are_not_modified() {
+    let etag = games_reply(None).headers()["etag"]
+        .to_str()
+        .unwrap()
+        .to_owned();
+    assert_eq!(games_reply(Some(etag)).status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(games_reply(Some("\"old\"".into())).status(), StatusCode::OK);
+}
+
+#[cfg(test)]
+#[test]
+fn weak_and_listed_etags_match() {
+    let etag = "\"abc\"";
+    assert!(etag_matches("W/\"abc\"", etag));
+    assert!(etag_matches("\"old\", W/\"abc\"", etag));
+    assert!(etag_matches("*", etag));
+    assert!(!etag_matches("\"old\", \"abcd\"", etag));
+}